@@ -2,11 +2,12 @@ use eframe::egui;
 
 use egui::{Area, Id};
 
-use three_mens_morris::types::OngoingGame;
+use three_mens_morris::types::{Move, OngoingGame};
 
 pub struct Client {
     current_state: OngoingGame,
     winner: char,
+    selected: Option<(usize, usize)>,
 }
 
 impl Default for Client {
@@ -14,6 +15,35 @@ impl Default for Client {
         Self {
             current_state: OngoingGame::new(),
             winner: ' ',
+            selected: None,
+        }
+    }
+}
+
+impl Client {
+    fn click(&mut self, row: usize, col: usize) {
+        // place a piece while any are left in hand,
+        // otherwise the first click picks a piece and the second one moves it.
+        if self.current_state.outcome().is_some() {
+            return;
+        }
+        if self.current_state.remaining(self.current_state.turn) > 0 {
+            if let Ok(new_move) = Move::coord_to_new_move(&row, &col) {
+                let _ = self.current_state.apply(new_move);
+            }
+            return;
+        }
+        match self.selected.take() {
+            Some((old_row, old_col)) => {
+                if let Ok(new_move) = Move::coord_to_move(&old_row, &old_col, &row, &col) {
+                    let _ = self.current_state.apply(new_move);
+                }
+            }
+            None => {
+                if self.current_state.board[row][col] == self.current_state.turn {
+                    self.selected = Some((row, col));
+                }
+            }
         }
     }
 }
@@ -58,7 +88,7 @@ impl eframe::App for Client {
                                                 ui.add_space(50.0);
                                             }
                                             if response.clicked() {
-                                                self.current_state.board[row][col] += 1;
+                                                self.click(row, col);
                                             }
                                             positions.push(response.rect.center());
                                         }
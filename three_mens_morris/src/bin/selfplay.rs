@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::process;
+
+use three_mens_morris::bot::{BotFiles, Registry};
+use three_mens_morris::rules::Variant;
+use three_mens_morris::selfplay::{self, PlayerConfig, SelfPlay};
+
+const USAGE: &str = "usage: selfplay GAMES OUTPUT.csv [--one BOT] [--two BOT] [--seed N] [--threads N] [--variant NAME]
+bots: random, search, mcts (default mcts for both), and book or network
+when TMM_BOOK or TMM_NETWORK name their files";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        fail("missing arguments");
+    }
+    let games = args[0]
+        .parse()
+        .unwrap_or_else(|_| fail("GAMES must be a number"));
+    let output = &args[1];
+    let registry = Registry::with_files(&BotFiles::from_env());
+    let mcts = PlayerConfig::from_name("mcts", &registry).unwrap();
+    let mut run = SelfPlay::new([mcts.clone(), mcts], games, 0);
+
+    // flags come in pairs after the two positional arguments
+    for pair in args[2..].chunks(2) {
+        let [flag, value] = pair else {
+            fail("flag without a value");
+        };
+        let bot =
+            || PlayerConfig::from_name(value, &registry).unwrap_or_else(|| fail("unknown bot"));
+        match flag.as_str() {
+            "--one" => run.players[0] = bot(),
+            "--two" => run.players[1] = bot(),
+            "--seed" => run.seed = value.parse().unwrap_or_else(|_| fail("bad seed")),
+            "--threads" => run.threads = value.parse().unwrap_or_else(|_| fail("bad thread count")),
+            "--variant" => {
+                run.variant = Variant::from_name(value).unwrap_or_else(|| fail("unknown variant"))
+            }
+            _ => fail("unknown flag"),
+        }
+    }
+
+    let samples = run.run().unwrap_or_else(|e| fail(&e.to_string()));
+    let file = File::create(output).unwrap_or_else(|e| fail(&e.to_string()));
+    selfplay::write_csv(&samples, &mut BufWriter::new(file))
+        .unwrap_or_else(|e| fail(&e.to_string()));
+    println!(
+        "{} games, {} positions written to {}",
+        games,
+        samples.len(),
+        output
+    );
+}
@@ -7,10 +7,13 @@ use uuid::Uuid;
 use actix_web::{get, post, patch, error, web, App, Error, HttpResponse, HttpServer, Responder};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use three_mens_morris::stupid_bot::StupidBot;
+use three_mens_morris::bot::{Bot, BotFiles, Registry};
 use three_mens_morris::database::TmmDbClient;
+use three_mens_morris::error::GameError;
 use three_mens_morris::referee::Referee;
+use three_mens_morris::rules::Variant;
 use three_mens_morris::types::{ GameHistory, OngoingGame, Move};
 
 const MAX_SIZE: usize = 262_144; // max payload size is 256k
@@ -25,6 +28,24 @@ async fn hello() -> impl Responder {
 #[derive(Serialize, Deserialize)]
 pub struct NewGamePayload {
     pub user_id: String,
+    // variant key like "achi", three men's morris when missing.
+    // games are stored as 3x3 OngoingGames, so only three_mens, tapatan, achi and nine_holes
+    // can be played here; the larger boards (six_mens, nine_mens, twelve_mens, morabaraba)
+    // are answered with 400 UnsupportedVariant and are only playable through MorrisGame
+    #[serde(default)]
+    pub variant: Option<String>,
+    // starting position as a FEN-like string (see fen.rs), the empty board when missing.
+    // a string without a variant key is read as the variant field; naming another one is refused.
+    #[serde(default)]
+    pub fen: Option<String>,
+    // quiet moves before the game is drawn, 0 for no limit.
+    // the limit of the fen or the variant when missing
+    #[serde(default)]
+    pub move_limit: Option<u32>,
+    // opponent: a bot name ("random", "mcts", "search", and "book" or "network" when their
+    // files are configured) or a difficulty ("easy", "medium", "hard"), random when missing
+    #[serde(default)]
+    pub bot: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,8 +64,41 @@ impl MovePayload {
     }
 }
 
+fn game_error_response(e: &GameError) -> HttpResponse {
+    // every rules error is caused by the request, so they all map to 4xx.
+    match e {
+        GameError::InvalidNotation { .. }
+        | GameError::OutOfBoard { .. }
+        | GameError::UnknownVariant { .. }
+        | GameError::UnsupportedVariant { .. }
+        | GameError::UnknownBot { .. }
+        | GameError::InvalidFen { .. } => {
+            HttpResponse::BadRequest().json(e.to_string())
+        }
+        GameError::PlayerNotInGame { .. } => HttpResponse::Forbidden().json(e.to_string()),
+        GameError::NotYourTurn | GameError::GameOver => HttpResponse::Conflict().json(e.to_string()),
+        _ => HttpResponse::UnprocessableEntity().json(e.to_string()),
+    }
+}
+
+async fn bot_move(
+    bot: Box<dyn Bot>,
+    mut game: OngoingGame,
+) -> Result<(OngoingGame, Result<(), GameError>), Error> {
+    // a search can take seconds, so it runs on the blocking pool instead of the worker
+    let moved = web::block(move || {
+        let result = bot.make_move(&mut game);
+        (game, result)
+    })
+    .await?;
+    Ok(moved)
+}
+
 #[patch("/play")]
-async fn play(mut payload: web::Payload) -> Result<HttpResponse, Error> {
+async fn play(
+    registry: web::Data<Registry>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, Error> {
     // payload is a stream of Bytes objects
     // expect to have user_id and move in (char, u8) in payload
     let mut body = web::BytesMut::new();
@@ -61,32 +115,69 @@ async fn play(mut payload: web::Payload) -> Result<HttpResponse, Error> {
 
     let user_id = &obj.user_id;
     println!("move_code: {}", &obj.move_code);
-    // TODO: check if move is valid
-    let new_move = Move::string_to_move(&obj.move_code).unwrap();
+    let new_move = match Move::string_to_move(&obj.move_code) {
+        Ok(new_move) => new_move,
+        Err(e) => return Ok(game_error_response(&e)),
+    };
 
     println!("got user ID {}", user_id);
     println!("got move {}", new_move.print());
     
     // Retrieve ongoing game
     let client = TmmDbClient::new().await;
-    let ongoing_game = client.get_ongoing_game_by_user_id(user_id).await.unwrap();
+    let mut ongoing_game = match client.get_ongoing_game_by_user_id(user_id).await {
+        Ok(game) => game,
+        Err(()) => return Ok(HttpResponse::NotFound().json("No game found for the user.")),
+    };
+
+    // engine validates the move and that it is the user's turn
+    if let Err(e) = ongoing_game.apply_as(user_id, new_move) {
+        return Ok(game_error_response(&e));
+    }
+
+    // let bot answer unless the user just ended the game
+    if ongoing_game.outcome().is_none() {
+        // games created before bots could be picked have the random bot
+        let bot = registry.in_game(&ongoing_game).or_else(|| registry.by_name("random"));
+        if let Some(bot) = bot {
+            // the game is not saved, so the user can send the move again
+            let (moved, result) = bot_move(bot, ongoing_game).await?;
+            ongoing_game = moved;
+            if let Err(e) = result {
+                println!("bot failed to move: {}", e);
+                return Ok(HttpResponse::InternalServerError().json(
+                    format!("Bot failed to move: {}", e)));
+            }
+        }
+    }
 
-    // Check whether piece is new or not
-    if new_move.new_col.is_none() {
-        // move is new
-        
+    let result = client.update_onging_game(&ongoing_game).await;
+    if let Err(e) = result {
+        return Ok(HttpResponse::InternalServerError().json(
+            format!("Failed to update game: {}", e)));
+    }
+    // finished games, won or drawn, are kept in the history collection
+    if let Some(history) = GameHistory::from_game(&ongoing_game) {
+        if let Err(e) = client.insert_history(&history).await {
+            return Ok(HttpResponse::InternalServerError().json(
+                format!("Failed to record game: {}", e)));
+        }
     }
 
-    // Return newly generated game_id and the opponent.
+    // Return the game after user's and bot's moves.
     let mut response = HashMap::new();
-    // response.insert("game_id", new_game._id);
-    response.insert("player one", ongoing_game.player_one);
-    response.insert("player two", ongoing_game.player_two);
-    // response.insert("opponent_id", opponent.to_string());
-    // response.insert("turn", new_state.turn.to_string());
-    response.insert("player_one_remaining", ongoing_game.player_one_remaining.to_string());
-    response.insert("player_two_remaining", ongoing_game.player_two_remaining.to_string());
-    // response.insert("board", new_state.flatten_board());
+    response.insert("game_id", json!(ongoing_game._id));
+    response.insert("player one", json!(ongoing_game.player_one));
+    response.insert("player two", json!(ongoing_game.player_two));
+    response.insert("turn", json!(ongoing_game.whose_turn));
+    response.insert("player_one_remaining", json!(ongoing_game.player_one_remaining.to_string()));
+    response.insert("player_two_remaining", json!(ongoing_game.player_two_remaining.to_string()));
+    response.insert("board", json!(ongoing_game.flatten_board()));
+    if let Some(history) = GameHistory::from_game(&ongoing_game) {
+        // winner is empty on a draw, result tells how the game ended
+        response.insert("winner", json!(history.winner));
+        response.insert("result", json!(history.result));
+    }
     Ok(HttpResponse::Ok().json(response))
 }
 
@@ -97,7 +188,10 @@ async fn get_game(path: web::Path<String,>) -> Result<HttpResponse, Error> {
     
     // Retrieve ongoing game
     let client = TmmDbClient::new().await;
-    let ongoing_game = client.get_ongoing_game(&game_id).await.unwrap();
+    let ongoing_game = match client.get_ongoing_game(&game_id).await {
+        Ok(game) => game,
+        Err(()) => return Ok(HttpResponse::NotFound().json("No game found with the id.")),
+    };
 
     // // Return newly generated game_id and the opponent.
     let mut response = HashMap::new();
@@ -121,7 +215,10 @@ async fn get_games_by_player_id(path: web::Path<String,>) -> Result<HttpResponse
     
     // Retrieve ongoing game
     let client = TmmDbClient::new().await;
-    let ongoing_games = client.get_all_ongoing_games_by_user_id(&player_id).await.unwrap();
+    let ongoing_games = match client.get_all_ongoing_games_by_user_id(&player_id).await {
+        Ok(games) => games,
+        Err(()) => return Ok(HttpResponse::InternalServerError().json("Failed to look up games.")),
+    };
 
     let mut response = HashMap::new();
     for (i, game) in ongoing_games.iter().enumerate() {
@@ -132,8 +229,52 @@ async fn get_games_by_player_id(path: web::Path<String,>) -> Result<HttpResponse
     Ok(HttpResponse::Ok().json(response))
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ResignPayload {
+    pub user_id: String,
+}
+
+#[patch("/resign")]
+async fn resign(mut payload: web::Payload) -> Result<HttpResponse, Error> {
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        // limit max size of in-memory payload
+        if (body.len() + chunk.len()) > MAX_SIZE {
+            return Err(error::ErrorBadRequest("overflow"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let obj = serde_json::from_slice::<ResignPayload>(&body)?;
+
+    let client = TmmDbClient::new().await;
+    let mut ongoing_game = match client.get_ongoing_game_by_user_id(&obj.user_id).await {
+        Ok(game) => game,
+        Err(()) => return Ok(HttpResponse::NotFound().json("No game found for the user.")),
+    };
+    let result = match ongoing_game.resign(&obj.user_id) {
+        Ok(result) => result,
+        Err(e) => return Ok(game_error_response(&e)),
+    };
+
+    if let Err(e) = client.update_onging_game(&ongoing_game).await {
+        return Ok(HttpResponse::InternalServerError().json(
+            format!("Failed to update game: {}", e)));
+    }
+    if let Some(history) = GameHistory::from_game(&ongoing_game) {
+        if let Err(e) = client.insert_history(&history).await {
+            return Ok(HttpResponse::InternalServerError().json(
+                format!("Failed to record game: {}", e)));
+        }
+    }
+    Ok(HttpResponse::Ok().json(result))
+}
+
 #[post("/new")]
-async fn start_new_game(mut payload: web::Payload) -> Result<HttpResponse, Error> {
+async fn start_new_game(
+    registry: web::Data<Registry>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, Error> {
     let client: TmmDbClient = TmmDbClient::new().await;
 
     // payload is a stream of Bytes objects
@@ -151,16 +292,43 @@ async fn start_new_game(mut payload: web::Payload) -> Result<HttpResponse, Error
     let obj = serde_json::from_slice::<NewGamePayload>(&body)?;
     // TODO: check user id valid
 
-    // initialize new OngoingGame
-    let mut new_game: OngoingGame = OngoingGame::new();
+    // initialize new OngoingGame with the requested rules
+    let variant = match &obj.variant {
+        Some(name) => match Variant::from_name(name) {
+            Some(variant) => Some(variant),
+            None => {
+                let e = GameError::UnknownVariant { name: name.clone() };
+                return Ok(game_error_response(&e));
+            }
+        },
+        None => None,
+    };
+    let starting_game = match (&obj.fen, variant) {
+        (Some(fen), Some(variant)) => OngoingGame::from_fen_with_variant(fen, variant),
+        (Some(fen), None) => OngoingGame::from_fen(fen),
+        (None, variant) => OngoingGame::with_variant(variant.unwrap_or_default()),
+    };
+    let mut new_game: OngoingGame = match starting_game {
+        Ok(game) => game,
+        Err(e) => return Ok(game_error_response(&e)),
+    };
+    if let Some(limit) = obj.move_limit {
+        new_game.move_limit = match limit {
+            0 => None,
+            limit => Some(limit),
+        };
+    }
     // generate a uuid for a game.
     new_game._id = Uuid::new_v4().to_string();
     // as proof of concept, let user pass down their uuid in the payload
     let user_id = &obj.user_id;
 
-    // create stupid bot opponent
-    let bot = StupidBot::new();
-    let opponent = bot.get_id();
+    // create the requested bot opponent
+    let bot = match registry.find(obj.bot.as_deref().unwrap_or("random")) {
+        Ok(bot) => bot,
+        Err(e) => return Ok(game_error_response(&e)),
+    };
+    let opponent = bot.id().to_string();
     
     // flip a coin:
     // head -> user=player one
@@ -179,13 +347,26 @@ async fn start_new_game(mut payload: web::Payload) -> Result<HttpResponse, Error
         }
     }
 
-    new_game.whose_turn = new_game.player_one.clone();
-    // let bot play first
-    let _ = bot.place_random_new_piece(&mut new_game);
+    new_game.whose_turn = match new_game.turn {
+        1 => new_game.player_one.clone(),
+        _ => new_game.player_two.clone(),
+    };
+    // let bot play first if it is to move
+    if new_game.outcome().is_none() && new_game.whose_turn == opponent {
+        let (moved, result) = bot_move(bot, new_game).await?;
+        new_game = moved;
+        if let Err(e) = result {
+            println!("bot failed to move: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(
+                format!("Bot failed to move: {}", e)));
+        }
+    }
 
     let player_one_remaining = new_game.player_one_remaining.to_string();
     let player_two_remaining = new_game.player_two_remaining.to_string();
     let flattened_board = new_game.flatten_board();
+    let variant_name = new_game.variant.name().to_string();
+    let fen = new_game.to_fen();
 
     // create the ongoing game to database
     let result = client.insert_onging_game(&new_game).await;
@@ -206,6 +387,8 @@ async fn start_new_game(mut payload: web::Payload) -> Result<HttpResponse, Error
     response.insert("player_one_remaining", &player_one_remaining);
     response.insert("player_two_remaining", &player_two_remaining);
     response.insert("board", &flattened_board);
+    response.insert("variant", &variant_name);
+    response.insert("fen", &fen);
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -232,10 +415,15 @@ async fn main() -> std::io::Result<()> {
 
     // let find_doc = client.find_history_by_player(Uuid::parse_str("c152e455-5609-4031-afeb-fa63b938de5f").unwrap()).await.unwrap();
     // println!("{:?}", find_doc);
-    HttpServer::new(|| {
+    // one registry for all workers, bots are built from it per request.
+    // the book and network bots come in when TMM_BOOK and TMM_NETWORK name their files
+    let registry = web::Data::new(Registry::with_files(&BotFiles::from_env()));
+    HttpServer::new(move || {
         App::new()
+            .app_data(registry.clone())
             .service(hello)
             .service(play)
+            .service(resign)
             .service(echo)
             .service(get_game)
             .service(start_new_game)
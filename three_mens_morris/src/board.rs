@@ -0,0 +1,293 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+// Board is the topology a game is played on, kept as data:
+// - points pieces can stand on, each with a name used in notation and a display position
+// - edges between points a piece can move along
+// - lines that win (or form a mill) when one player fills them
+// Rules never hard-code adjacency; they ask the board.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Point {
+    pub name: String,
+    // display position, column then row
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Board {
+    pub name: String,
+    pub points: Vec<Point>,
+    pub edges: Vec<(usize, usize)>,
+    pub lines: Vec<Vec<usize>>,
+    // neighbours of every point, derived from edges
+    adjacency: Vec<Vec<usize>>,
+}
+
+static THREE_MENS: OnceLock<Board> = OnceLock::new();
+static NINE_HOLES: OnceLock<Board> = OnceLock::new();
+static SIX_MENS: OnceLock<Board> = OnceLock::new();
+static NINE_MENS: OnceLock<Board> = OnceLock::new();
+static TWELVE_MENS: OnceLock<Board> = OnceLock::new();
+
+impl Board {
+    pub fn new(
+        name: &str,
+        points: Vec<Point>,
+        edges: Vec<(usize, usize)>,
+        lines: Vec<Vec<usize>>,
+    ) -> Board {
+        let mut adjacency = vec![Vec::new(); points.len()];
+        for &(a, b) in &edges {
+            if a >= points.len() || b >= points.len() || a == b {
+                continue;
+            }
+            if !adjacency[a].contains(&b) {
+                adjacency[a].push(b);
+            }
+            if !adjacency[b].contains(&a) {
+                adjacency[b].push(a);
+            }
+        }
+        for neighbours in adjacency.iter_mut() {
+            neighbours.sort();
+        }
+        Board {
+            name: name.to_string(),
+            points,
+            edges,
+            lines,
+            adjacency,
+        }
+    }
+
+    pub fn three_mens() -> &'static Board {
+        // 3x3 grid indexed row by row, a1 = 0, b1 = 1, ... c3 = 8.
+        // orthogonal neighbours are connected and the corners connect through the center.
+        THREE_MENS.get_or_init(|| Board::three_by_three("three men's morris", true))
+    }
+
+    pub fn nine_holes() -> &'static Board {
+        // the same grid without diagonals: rows and columns only
+        NINE_HOLES.get_or_init(|| Board::three_by_three("nine holes", false))
+    }
+
+    pub fn three_by_three(name: &str, diagonals: bool) -> Board {
+        // 3x3 grid, with or without the two diagonals as lines and paths through the center.
+        // edge-to-edge diagonals (b1-a2, ...) are never connected.
+        let mut edges = Vec::new();
+        for row in 0..3 {
+            for col in 0..3 {
+                let index = row * 3 + col;
+                if col < 2 {
+                    edges.push((index, index + 1));
+                }
+                if row < 2 {
+                    edges.push((index, index + 3));
+                }
+            }
+        }
+        let mut lines = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0, 3, 6],
+            vec![1, 4, 7],
+            vec![2, 5, 8],
+        ];
+        if diagonals {
+            edges.extend([(0, 4), (2, 4), (6, 4), (8, 4)]);
+            lines.extend([vec![0, 4, 8], vec![2, 4, 6]]);
+        }
+        Board::new(name, grid_points(3), edges, lines)
+    }
+
+    pub fn from_names(name: &str, points: &[&str], edges: &[(&str, &str)], lines: &[&[&str]]) -> Board {
+        // build a board from point names like "d2".
+        // display position comes from the name, column letter then row number.
+        let index = |point: &str| points.iter().position(|p| *p == point).unwrap();
+        let board_points = points
+            .iter()
+            .map(|p| {
+                let mut chars = p.chars();
+                let col = chars.next().unwrap();
+                let row: i32 = chars.as_str().parse().unwrap();
+                Point {
+                    name: p.to_string(),
+                    x: col as i32 - 'a' as i32,
+                    y: row - 1,
+                }
+            })
+            .collect();
+        let board_edges = edges.iter().map(|(a, b)| (index(a), index(b))).collect();
+        let board_lines = lines
+            .iter()
+            .map(|line| line.iter().map(|p| index(p)).collect())
+            .collect();
+        Board::new(name, board_points, board_edges, board_lines)
+    }
+
+    pub fn six_mens() -> &'static Board {
+        // two nested squares on a 5x5 grid, joined at the middle of each side.
+        // only the sides are mills, the joints are just paths.
+        SIX_MENS.get_or_init(|| {
+            let mut edges = consecutive_pairs(&SIX_MENS_LINES);
+            edges.extend([("c1", "c2"), ("a3", "b3"), ("d3", "e3"), ("c4", "c5")]);
+            Board::from_names("six men's morris", &SIX_MENS_POINTS, &edges, &SIX_MENS_LINES)
+        })
+    }
+
+    pub fn nine_mens() -> &'static Board {
+        // three nested squares on a 7x7 grid, joined at the middle of each side.
+        // every side and every joint is a mill, and pieces move along them.
+        NINE_MENS.get_or_init(|| {
+            Board::from_names(
+                "nine men's morris",
+                &NINE_MENS_POINTS,
+                &consecutive_pairs(&NINE_MENS_LINES),
+                &NINE_MENS_LINES,
+            )
+        })
+    }
+
+    pub fn twelve_mens() -> &'static Board {
+        // the nine men's board with the corners of the squares joined diagonally.
+        // the diagonals are mills too.
+        TWELVE_MENS.get_or_init(|| {
+            let lines: Vec<&[&str]> = NINE_MENS_LINES
+                .iter()
+                .chain(TWELVE_MENS_DIAGONALS.iter())
+                .copied()
+                .collect();
+            Board::from_names(
+                "twelve men's morris",
+                &NINE_MENS_POINTS,
+                &consecutive_pairs(&lines),
+                &lines,
+            )
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn neighbours(&self, point: usize) -> &[usize] {
+        match self.adjacency.get(point) {
+            Some(neighbours) => neighbours,
+            None => &[],
+        }
+    }
+
+    pub fn is_adjacent(&self, from: usize, to: usize) -> bool {
+        self.neighbours(from).contains(&to)
+    }
+
+    pub fn lines_through(&self, point: usize) -> impl Iterator<Item = &Vec<usize>> {
+        self.lines.iter().filter(move |line| line.contains(&point))
+    }
+
+    pub fn point_name(&self, point: usize) -> Option<&str> {
+        self.points.get(point).map(|p| p.name.as_str())
+    }
+
+    pub fn point_index(&self, name: &str) -> Option<usize> {
+        self.points
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn completed_line(&self, cells: &[u8]) -> Option<u8> {
+        // owner of the first line filled by a single player
+        for line in &self.lines {
+            let owner = cells[line[0]];
+            if owner != 0 && line.iter().all(|&point| cells[point] == owner) {
+                return Some(owner);
+            }
+        }
+        None
+    }
+}
+
+const SIX_MENS_POINTS: [&str; 16] = [
+    "a1", "c1", "e1", "b2", "c2", "d2", "a3", "b3",
+    "d3", "e3", "b4", "c4", "d4", "a5", "c5", "e5",
+];
+
+const SIX_MENS_LINES: [&[&str]; 8] = [
+    // outer square
+    &["a1", "c1", "e1"],
+    &["a5", "c5", "e5"],
+    &["a1", "a3", "a5"],
+    &["e1", "e3", "e5"],
+    // inner square
+    &["b2", "c2", "d2"],
+    &["b4", "c4", "d4"],
+    &["b2", "b3", "b4"],
+    &["d2", "d3", "d4"],
+];
+
+const NINE_MENS_POINTS: [&str; 24] = [
+    "a1", "d1", "g1", "b2", "d2", "f2", "c3", "d3", "e3", "a4", "b4", "c4",
+    "e4", "f4", "g4", "c5", "d5", "e5", "b6", "d6", "f6", "a7", "d7", "g7",
+];
+
+const NINE_MENS_LINES: [&[&str]; 16] = [
+    // horizontal
+    &["a1", "d1", "g1"],
+    &["b2", "d2", "f2"],
+    &["c3", "d3", "e3"],
+    &["a4", "b4", "c4"],
+    &["e4", "f4", "g4"],
+    &["c5", "d5", "e5"],
+    &["b6", "d6", "f6"],
+    &["a7", "d7", "g7"],
+    // vertical
+    &["a1", "a4", "a7"],
+    &["b2", "b4", "b6"],
+    &["c3", "c4", "c5"],
+    &["d1", "d2", "d3"],
+    &["d5", "d6", "d7"],
+    &["e3", "e4", "e5"],
+    &["f2", "f4", "f6"],
+    &["g1", "g4", "g7"],
+];
+
+const TWELVE_MENS_DIAGONALS: [&[&str]; 4] = [
+    &["a1", "b2", "c3"],
+    &["g1", "f2", "e3"],
+    &["a7", "b6", "c5"],
+    &["g7", "f6", "e5"],
+];
+
+fn consecutive_pairs<'a>(lines: &[&[&'a str]]) -> Vec<(&'a str, &'a str)> {
+    // neighbouring points of every line, for boards where pieces move along the lines
+    let mut pairs = Vec::new();
+    for line in lines {
+        for pair in line.windows(2) {
+            pairs.push((pair[0], pair[1]));
+        }
+    }
+    pairs
+}
+
+pub fn grid_points(size: usize) -> Vec<Point> {
+    // size x size points named a1, b1, ... row by row
+    let mut points = Vec::new();
+    for row in 0..size {
+        for col in 0..size {
+            points.push(Point {
+                name: format!("{}{}", (b'a' + col as u8) as char, row + 1),
+                x: col as i32,
+                y: row as i32,
+            });
+        }
+    }
+    points
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::position::{bits, Position, POINTS};
+use crate::rules::Variant;
+use crate::types::{Move, OngoingGame};
+
+// Book reads the opening tree written by the Python simulator
+// (three_mens_morris_v2/simulator, weight.json and expanded.json) so Rust bots can play from it.
+//
+// Keys come from board_to_key in main.py: the points of player one, "_", the points of player two,
+// so "01_2" has player one on 0 and 1 and player two on 2. Points are numbered like Position.
+// Layer is the number of plies played, player one is to move on even layers.
+// The board after 8 plies ends with "-", to tell it from the same board after 6 plies.
+//
+// Every game added one to wins and visits of its nodes on top of its result (update_weight),
+// so the share of games won by the player to move is 2 * wins / visits - 1.
+// Only three men's morris is in the book.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    pub layer: u32,
+    pub game_over: bool,
+    pub wins: u64,
+    pub visits: u64,
+    // keys of the nodes one ply later
+    pub children: Vec<String>,
+}
+
+impl Node {
+    pub fn win_rate(&self) -> Option<f64> {
+        // share of games won by the player to move, None when no game went through
+        if self.visits == 0 {
+            return None;
+        }
+        Some((2.0 * self.wins as f64 / self.visits as f64 - 1.0).clamp(0.0, 1.0))
+    }
+}
+
+pub struct Book {
+    nodes: HashMap<String, Node>,
+}
+
+fn invalid(reason: String) -> GameError {
+    GameError::InvalidBook { reason }
+}
+
+pub fn parse_key(key: &str) -> Result<[u16; 2], GameError> {
+    // piece masks of player one and player two, the "-" marker is left to the layer
+    let board = key.strip_suffix('-').unwrap_or(key);
+    let Some((one, two)) = board.split_once('_') else {
+        return Err(invalid(format!("key {} has no \"_\"", key)));
+    };
+    let mut pieces = [0u16; 2];
+    for (player, points) in [one, two].iter().enumerate() {
+        for c in points.chars() {
+            let point = match c.to_digit(10) {
+                Some(point) if (point as usize) < POINTS => point,
+                _ => return Err(invalid(format!("key {} has no point {}", key, c))),
+            };
+            if (pieces[0] | pieces[1]) & 1 << point != 0 {
+                return Err(invalid(format!("key {} repeats point {}", key, point)));
+            }
+            pieces[player] |= 1 << point;
+        }
+    }
+    Ok(pieces)
+}
+
+pub fn to_key(pieces: [u16; 2], layer: u32) -> String {
+    let points = |mask: u16| {
+        bits(mask)
+            .map(|point| point.to_string())
+            .collect::<String>()
+    };
+    let marker = if layer >= 8 && layer % 2 == 0 {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}_{}{}", points(pieces[0]), points(pieces[1]), marker)
+}
+
+pub fn key_to_game(key: &str, layer: u32) -> Result<OngoingGame, GameError> {
+    // the position of a key, without history
+    let pieces = parse_key(key)?;
+    let placed = [pieces[0].count_ones(), pieces[1].count_ones()];
+    let mut game = OngoingGame::new();
+    // placements alternate starting with player one until both have three on the board
+    let expected = [layer.div_ceil(2).min(3), (layer / 2).min(3)];
+    if placed != expected || key.ends_with('-') != (layer >= 8 && layer % 2 == 0) {
+        return Err(invalid(format!("key {} cannot be layer {}", key, layer)));
+    }
+    for (player, &mask) in pieces.iter().enumerate() {
+        for point in bits(mask) {
+            game.board[point / 3][point % 3] = player as u8 + 1;
+        }
+    }
+    game.player_one_remaining = 3 - placed[0] as u8;
+    game.player_two_remaining = 3 - placed[1] as u8;
+    if layer % 2 == 1 {
+        game.update_turn();
+    }
+    Ok(game)
+}
+
+pub fn layer(game: &OngoingGame) -> Option<u32> {
+    // plies played: the history when it accounts for the board, else the fewest plies
+    // that put the pieces on the board with the right player to move. None off the book's rules.
+    if game.variant != Variant::ThreeMens {
+        return None;
+    }
+    let placed = game.cells().iter().filter(|&&cell| cell != 0).count() as u32;
+    let mut layer = (game.moves.len() as u32).max(placed);
+    if (layer % 2 == 0) != (game.turn == 1) {
+        layer += 1;
+    }
+    Some(layer)
+}
+
+impl Book {
+    pub fn from_json(json: &str) -> Result<Book, GameError> {
+        // every key is checked against its layer, so lookups can trust the book
+        let nodes: HashMap<String, Node> =
+            serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        for (key, node) in &nodes {
+            key_to_game(key, node.layer)?;
+            if node.wins > node.visits {
+                return Err(invalid(format!("key {} has more wins than visits", key)));
+            }
+        }
+        Ok(Book { nodes })
+    }
+
+    pub fn load(path: &Path) -> Result<Book, GameError> {
+        let json =
+            fs::read_to_string(path).map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+        Book::from_json(&json)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, key: &str) -> Option<&Node> {
+        self.nodes.get(key)
+    }
+
+    pub fn game(&self, key: &str) -> Option<OngoingGame> {
+        let node = self.nodes.get(key)?;
+        key_to_game(key, node.layer).ok()
+    }
+
+    pub fn lookup(&self, game: &OngoingGame) -> Option<&Node> {
+        let position = Position::from_game(game);
+        self.nodes.get(&to_key(position.pieces, layer(game)?))
+    }
+
+    pub fn moves(&self, game: &OngoingGame) -> Vec<(Move, &Node)> {
+        // legal moves leading to a position of the book, with its node
+        let Some(layer) = layer(game) else {
+            return Vec::new();
+        };
+        let position = Position::from_game(game);
+        position
+            .legal_moves()
+            .iter()
+            .filter_map(|&new_move| {
+                let mut child = position;
+                child.apply(new_move);
+                let node = self.nodes.get(&to_key(child.pieces, layer + 1))?;
+                Some((Position::to_move(&new_move), node))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use crate::book::Book;
+use crate::bot::Bot;
+use crate::error::GameError;
+use crate::smart_bot::SmartBot;
+use crate::types::{Move, OngoingGame};
+
+// BookBot plays from the simulator's opening book while the game is in it,
+// then hands the game to a fallback strategy.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    // the move whose games the mover won most often
+    WinRate,
+    // the move played most often
+    Visits,
+}
+
+pub struct BookBot {
+    player_id: String,
+    // shared, the registry builds a bot per lookup from one loaded book
+    book: Arc<Book>,
+    pub choice: Choice,
+    // moves seen in fewer games are not trusted
+    pub min_visits: u64,
+    // plays once the game leaves the book
+    fallback: Box<dyn Bot>,
+}
+
+impl BookBot {
+    pub fn new(id: &str, book: impl Into<Arc<Book>>) -> BookBot {
+        // out of book the search takes over
+        BookBot::with_fallback(id, book, Box::new(SmartBot::new(id)))
+    }
+
+    pub fn with_fallback(id: &str, book: impl Into<Arc<Book>>, fallback: Box<dyn Bot>) -> BookBot {
+        BookBot {
+            player_id: String::from(id),
+            book: book.into(),
+            choice: Choice::WinRate,
+            min_visits: 10,
+            fallback,
+        }
+    }
+
+    pub fn book(&self) -> &Book {
+        &self.book
+    }
+
+    pub fn book_move(&self, state: &OngoingGame) -> Option<Move> {
+        // None once the game has left the book
+        let candidates = self
+            .book
+            .moves(state)
+            .into_iter()
+            .filter(|(_, node)| node.visits >= self.min_visits.max(1));
+        match self.choice {
+            // games in the simulator end with a winner, so the mover won those the opponent did not
+            Choice::WinRate => candidates
+                .map(|(new_move, node)| (new_move, 1.0 - node.win_rate().unwrap_or(0.0), node))
+                .max_by(|(_, a, a_node), (_, b, b_node)| {
+                    a.total_cmp(b).then(a_node.visits.cmp(&b_node.visits))
+                })
+                .map(|(new_move, _, _)| new_move),
+            Choice::Visits => candidates
+                .max_by_key(|(_, node)| node.visits)
+                .map(|(new_move, _)| new_move),
+        }
+    }
+}
+
+impl Bot for BookBot {
+    fn id(&self) -> &str {
+        &self.player_id
+    }
+
+    fn name(&self) -> &str {
+        "book"
+    }
+
+    fn choose_move(&self, state: &OngoingGame) -> Result<Move, GameError> {
+        if state.outcome().is_some() {
+            return Err(GameError::GameOver);
+        }
+        match self.book_move(state) {
+            Some(new_move) => Ok(new_move),
+            None => self.fallback.choose_move(state),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::Book;
+use crate::book_bot::BookBot;
+use crate::error::GameError;
+use crate::mcts_bot::MctsBot;
+use crate::network::Network;
+use crate::network_bot::NetworkBot;
+use crate::smart_bot::SmartBot;
+use crate::stupid_bot::StupidBot;
+use crate::types::{Move, OngoingGame};
+
+// Bot is what the server, the CLI and the GUI play against.
+// choose_move only looks at the game; make_move plays the choice for the bot's id.
+// Registry maps names and difficulties to bots, so call sites never name a bot type:
+// a new bot is one register call (or one line in Registry::default).
+// The registry gives every bot its player id, bots do not pick their own.
+// The book and network bots need data files, Registry::with_files adds them when those load.
+
+pub trait Bot: Send + Sync {
+    // player id stored in games the bot plays
+    fn id(&self) -> &str;
+
+    // registry name, e.g. "random"
+    fn name(&self) -> &str;
+
+    fn choose_move(&self, game: &OngoingGame) -> Result<Move, GameError>;
+
+    fn make_move(&self, game: &mut OngoingGame) -> Result<(), GameError> {
+        // Err if it is not the bot's turn
+        let new_move = self.choose_move(game)?;
+        game.apply_as(self.id(), new_move)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn all() -> &'static [Difficulty] {
+        &[Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::all().iter().copied().find(|d| d.name() == name)
+    }
+}
+
+// data files of the bots that need one, e.g. weight.json of the Python simulator for the book
+// and the output of Model.export for the network
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BotFiles {
+    pub book: Option<PathBuf>,
+    pub network: Option<PathBuf>,
+}
+
+impl BotFiles {
+    pub fn from_env() -> BotFiles {
+        // TMM_BOOK and TMM_NETWORK, a bot whose variable is unset is left out
+        BotFiles {
+            book: env::var_os("TMM_BOOK").map(PathBuf::from),
+            network: env::var_os("TMM_NETWORK").map(PathBuf::from),
+        }
+    }
+}
+
+// builds the bot for the player id it is given
+type Build = Arc<dyn Fn(&str) -> Box<dyn Bot> + Send + Sync>;
+
+// a registered bot that can be built any number of times, e.g. once per self-play game
+#[derive(Clone)]
+pub struct BotFactory {
+    name: String,
+    id: String,
+    build: Build,
+}
+
+impl BotFactory {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn build(&self) -> Box<dyn Bot> {
+        // a fresh bot every time, so games do not share state
+        (self.build)(&self.id)
+    }
+}
+
+impl fmt::Debug for BotFactory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BotFactory")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl PartialEq for BotFactory {
+    fn eq(&self, other: &BotFactory) -> bool {
+        // the same registration, a bot registered again is a different one
+        self.name == other.name && Arc::ptr_eq(&self.build, &other.build)
+    }
+}
+
+struct Entry {
+    factory: BotFactory,
+    difficulty: Difficulty,
+}
+
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        // the bots of this crate, weakest first
+        let mut registry = Registry::new();
+        registry.register("random", Difficulty::Easy, |id| {
+            Box::new(StupidBot::new(id))
+        });
+        registry.register("mcts", Difficulty::Medium, |id| Box::new(MctsBot::new(id)));
+        registry.register("search", Difficulty::Hard, |id| Box::new(SmartBot::new(id)));
+        registry
+    }
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_files(files: &BotFiles) -> Registry {
+        // the default bots and those whose file loads, each file is read once here.
+        // a file that does not load is reported and its bot left out.
+        let mut registry = Registry::default();
+        if let Some(path) = &files.network {
+            match Network::load(path) {
+                Ok(network) => registry.register("network", Difficulty::Medium, move |id| {
+                    Box::new(NetworkBot::new(id, network.clone()))
+                }),
+                Err(e) => eprintln!("network bot left out: {}", e),
+            }
+        }
+        if let Some(path) = &files.book {
+            match Book::load(path) {
+                Ok(book) => {
+                    let book = Arc::new(book);
+                    registry.register("book", Difficulty::Hard, move |id| {
+                        Box::new(BookBot::new(id, book.clone()))
+                    });
+                }
+                Err(e) => eprintln!("book bot left out: {}", e),
+            }
+        }
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        name: &str,
+        difficulty: Difficulty,
+        build: impl Fn(&str) -> Box<dyn Bot> + Send + Sync + 'static,
+    ) {
+        // the player id stored in games is "bot-" and the name,
+        // a bot registered again replaces the old entry
+        let entry = Entry {
+            factory: BotFactory {
+                name: name.to_string(),
+                id: format!("bot-{}", name),
+                build: Arc::new(build),
+            },
+            difficulty,
+        };
+        self.entries.retain(|old| old.factory.name != name);
+        self.entries.push(entry);
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(|entry| entry.factory.name())
+            .collect()
+    }
+
+    pub fn difficulty(&self, name: &str) -> Option<Difficulty> {
+        self.entries
+            .iter()
+            .find(|entry| entry.factory.name == name)
+            .map(|entry| entry.difficulty)
+    }
+
+    pub fn factory(&self, name: &str) -> Option<BotFactory> {
+        // for callers that build the same bot again and again
+        self.entries
+            .iter()
+            .find(|entry| entry.factory.name == name)
+            .map(|entry| entry.factory.clone())
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<Box<dyn Bot>> {
+        self.factory(name).map(|factory| factory.build())
+    }
+
+    pub fn by_difficulty(&self, difficulty: Difficulty) -> Option<Box<dyn Bot>> {
+        // first bot registered with the difficulty
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.difficulty == difficulty)?;
+        Some(entry.factory.build())
+    }
+
+    pub fn find(&self, name: &str) -> Result<Box<dyn Bot>, GameError> {
+        // a bot name or a difficulty name, for user input
+        let name = name.trim().to_lowercase();
+        self.by_name(&name)
+            .or_else(|| Difficulty::from_name(&name).and_then(|d| self.by_difficulty(d)))
+            .ok_or(GameError::UnknownBot { name })
+    }
+
+    pub fn in_game(&self, game: &OngoingGame) -> Option<Box<dyn Bot>> {
+        // the registered bot playing in a stored game
+        let entry = self.entries.iter().find(|entry| {
+            game.player_one == entry.factory.id || game.player_two == entry.factory.id
+        })?;
+        Some(entry.factory.build())
+    }
+}
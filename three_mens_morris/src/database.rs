@@ -55,17 +55,10 @@ impl TmmDbClient {
         }
     }
 
-    async fn insert_history(&self, doc: &GameHistory){
-        let res: InsertOneResult;
-        match self.game_history.insert_one(doc, None).await {
-            Ok(result) => {
-                res = result;
-                println!("Inserted a document with _id: {}", res.inserted_id);
-            },
-            Err(_) => {
-                println!("Cannot insert a document.")
-            },
-        };
+    pub async fn insert_history(&self, doc: &GameHistory) -> MongoResult<()> {
+        let res: InsertOneResult = self.game_history.insert_one(doc, None).await?;
+        println!("Inserted a document with _id: {}", res.inserted_id);
+        Ok(())
     }
 
     async fn get_all_history(&self) {
@@ -80,19 +73,22 @@ impl TmmDbClient {
             doc! { "$or": [{"player_one": &user_id }, {"player_two": &user_id }]},
             None
         ).await;
-        if result.is_ok() {
-            let ongoing_game = result.unwrap().unwrap();
-            return Ok(ongoing_game);
+        // Err when the lookup fails or there is no such game
+        match result {
+            Ok(Some(ongoing_game)) => Ok(ongoing_game),
+            _ => Err(()),
         }
-        return Err(());
     }
 
     pub async fn get_all_ongoing_games_by_user_id(&self, user_id: &String) -> Result<Vec<OngoingGame>, ()> {
         let mut games: Vec<OngoingGame> = Vec::new();
-        let mut cursor = self.ongoing_games.find(
+        let mut cursor = match self.ongoing_games.find(
             doc! { "$or": [{"player_one": &user_id }, {"player_two": &user_id }]},
             None
-        ).await.unwrap();
+        ).await {
+            Ok(cursor) => cursor,
+            Err(_) => return Err(()),
+        };
         while let Some(result) = cursor.next().await {
             match result {
                 Ok(doc) => {
@@ -112,11 +108,11 @@ impl TmmDbClient {
             doc! { "_id": _id},
             None
         ).await;
-        if result.is_ok() {
-            let ongoing_game = result.unwrap().unwrap();
-            return Ok(ongoing_game);
+        // Err when the lookup fails or there is no such game
+        match result {
+            Ok(Some(ongoing_game)) => Ok(ongoing_game),
+            _ => Err(()),
         }
-        return Err(());
     }
 
     pub async fn insert_onging_game(&self, doc: &OngoingGame) -> MongoResult<()> {
@@ -130,17 +126,13 @@ impl TmmDbClient {
         }
     }
 
-    async fn update_onging_game(&self, doc: &OngoingGame) {
+    pub async fn update_onging_game(&self, doc: &OngoingGame) -> MongoResult<()> {
         // This function assumes all validation is done by the caller.
+        // The whole game document is replaced since the engine may touch any field.
         let filter = doc! { "_id": doc.get_id() };
-        // assert state is different
-        let doc_bson= bson::to_bson(doc).unwrap();
-        let update_doc = match doc_bson {
-            bson::Bson::Document(document) => document,
-            _ => return (),
-        };
-        let res = self.ongoing_games.update_one(filter, update_doc, None).await.unwrap();
+        let res = self.ongoing_games.replace_one(filter, doc, None).await?;
         println!("Updated documents: {}", res.modified_count);
+        Ok(())
     }
 
     // pub async fn find_histories_by_player(&self, player_id: Uuid) -> Result<GameHistory, ()>{
@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Point};
+use crate::error::GameError;
+use crate::rules::RuleSet;
+
+// BoardDefinition is a morris variant written in a JSON or TOML file, so new boards
+// can be tried without touching Rust. Points are referenced by name everywhere.
+//
+// name = "triangle"
+// pieces = 3
+// points = [{ name = "a" }, { name = "b", x = 1, y = 0 }, { name = "c" }]
+// edges = [["a", "b"], ["b", "c"], ["c", "a"]]
+// lines = [["a", "b", "c"]]
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PointDefinition {
+    pub name: String,
+    // display position, laid out left to right when missing
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BoardDefinition {
+    pub name: String,
+    pub points: Vec<PointDefinition>,
+    pub edges: Vec<(String, String)>,
+    pub lines: Vec<Vec<String>>,
+    pub pieces: u8,
+    #[serde(default)]
+    pub removal: bool,
+    #[serde(default)]
+    pub flying: bool,
+    #[serde(default)]
+    pub full_board_draw: bool,
+    // no move limit when missing
+    #[serde(default)]
+    pub move_limit: Option<u32>,
+}
+
+impl BoardDefinition {
+    pub fn from_json(input: &str) -> Result<BoardDefinition, GameError> {
+        serde_json::from_str(input).map_err(|e| GameError::InvalidDefinition {
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn from_toml(input: &str) -> Result<BoardDefinition, GameError> {
+        toml::from_str(input).map_err(|e| GameError::InvalidDefinition {
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<BoardDefinition, GameError> {
+        // format is picked by extension, .toml or anything else as JSON
+        let input = fs::read_to_string(path).map_err(|e| GameError::InvalidDefinition {
+            reason: format!("{}: {}", path.display(), e),
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => BoardDefinition::from_toml(&input),
+            _ => BoardDefinition::from_json(&input),
+        }
+    }
+
+    pub fn to_rules(&self) -> Result<RuleSet, GameError> {
+        // validate the definition and build the RuleSet games are played with
+        let invalid = |reason: String| Err(GameError::InvalidDefinition { reason });
+        if self.points.is_empty() {
+            return invalid(String::from("no points"));
+        }
+
+        let mut points = Vec::new();
+        for (index, point) in self.points.iter().enumerate() {
+            // notation splits moves on '-' and captures on 'x' (see MorrisMove::parse)
+            if point.name.is_empty()
+                || point.name.contains(|c: char| {
+                    c == '-' || c.eq_ignore_ascii_case(&'x') || c.is_whitespace()
+                })
+            {
+                return invalid(format!(
+                    "point name {:?} must not be empty or contain 'x', '-' or spaces",
+                    point.name
+                ));
+            }
+            if points.iter().any(|p: &Point| p.name.eq_ignore_ascii_case(&point.name)) {
+                return Err(GameError::DuplicatePoint {
+                    name: point.name.clone(),
+                });
+            }
+            points.push(Point {
+                name: point.name.clone(),
+                x: point.x.unwrap_or(index as i32),
+                y: point.y.unwrap_or(0),
+            });
+        }
+        let index = |name: &str| -> Result<usize, GameError> {
+            points
+                .iter()
+                .position(|p| p.name.eq_ignore_ascii_case(name))
+                .ok_or(GameError::UnknownPoint {
+                    name: name.to_string(),
+                })
+        };
+
+        let mut edges = Vec::new();
+        for (a, b) in &self.edges {
+            let edge = (index(a)?, index(b)?);
+            if edge.0 == edge.1 {
+                return invalid(format!("edge from {} to itself", a));
+            }
+            edges.push(edge);
+        }
+        let mut lines = Vec::new();
+        for line in &self.lines {
+            let line = line.iter().map(|p| index(p)).collect::<Result<Vec<usize>, _>>()?;
+            if line.len() < 2 {
+                return invalid(String::from("lines need at least two points"));
+            }
+            if (1..line.len()).any(|i| line[..i].contains(&line[i])) {
+                return invalid(String::from("a line repeats a point"));
+            }
+            lines.push(line);
+        }
+        if self.pieces == 0 || self.pieces as usize > points.len() {
+            return invalid(format!(
+                "{} pieces do not fit on {} points",
+                self.pieces,
+                points.len()
+            ));
+        }
+
+        let board = Board::new(&self.name, points, edges, lines);
+        if let Some(point) = unreachable_point(&board) {
+            return Err(GameError::DisconnectedBoard {
+                point: board.point_name(point).unwrap().to_string(),
+            });
+        }
+        Ok(RuleSet {
+            name: self.name.clone(),
+            board,
+            pieces: self.pieces,
+            removal: self.removal,
+            flying: self.flying,
+            full_board_draw: self.full_board_draw,
+            move_limit: self.move_limit,
+        })
+    }
+}
+
+fn unreachable_point(board: &Board) -> Option<usize> {
+    // first point a piece on point 0 could never walk to
+    let mut seen = vec![false; board.len()];
+    let mut queue = VecDeque::from([0]);
+    seen[0] = true;
+    while let Some(point) = queue.pop_front() {
+        for &next in board.neighbours(point) {
+            if !seen[next] {
+                seen[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    seen.iter().position(|&reached| !reached)
+}
@@ -0,0 +1,407 @@
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::error::GameError;
+use crate::morris::{MorrisGame, MorrisMove};
+use crate::position::Position;
+use crate::types::{Move, OngoingGame};
+
+// Engine is the one place the rules are enforced on an OngoingGame.
+// Server, clients and bots should go through these methods instead of editing the board.
+// Goals:
+// - list every legal move for the player to move (legal_moves)
+// - apply and take back moves while keeping counters and turn in sync (apply, undo)
+// - tell whether the game is over and why (outcome, result)
+// The rules themselves live in MorrisGame; OngoingGame is the three men's morris view of it.
+// The MorrisGame is cached on the OngoingGame (MorrisCache) so queries do not convert the
+// whole move history each time.
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WinReason {
+    // rule 4: three pieces of the winner in one line
+    ThreeInARow,
+    // rule 5: the loser had no legal move on their turn
+    Blocked,
+    // games with removal: the loser can no longer make a line
+    TooFewPieces,
+    // the loser gave up
+    Resignation,
+    // the loser ran out of time
+    Timeout,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawReason {
+    // twelve men's morris: every point was taken during placement
+    FullBoard,
+    // the same position with the same player to move came up a third time
+    Repetition,
+    // RuleSet::move_limit moves in a row without a placement or removal
+    MoveLimit,
+}
+
+impl WinReason {
+    pub fn all() -> &'static [WinReason] {
+        &[
+            WinReason::ThreeInARow,
+            WinReason::Blocked,
+            WinReason::TooFewPieces,
+            WinReason::Resignation,
+            WinReason::Timeout,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // key used in game records
+        match self {
+            WinReason::ThreeInARow => "three_in_a_row",
+            WinReason::Blocked => "blocked",
+            WinReason::TooFewPieces => "too_few_pieces",
+            WinReason::Resignation => "resignation",
+            WinReason::Timeout => "timeout",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WinReason> {
+        WinReason::all().iter().copied().find(|reason| reason.name() == name)
+    }
+}
+
+impl DrawReason {
+    pub fn all() -> &'static [DrawReason] {
+        &[DrawReason::FullBoard, DrawReason::Repetition, DrawReason::MoveLimit]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // key used in game records
+        match self {
+            DrawReason::FullBoard => "full_board",
+            DrawReason::Repetition => "repetition",
+            DrawReason::MoveLimit => "move_limit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DrawReason> {
+        DrawReason::all().iter().copied().find(|reason| reason.name() == name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win { player: u8, reason: WinReason },
+    Draw { reason: DrawReason },
+}
+
+// GameResult is how a finished game is reported and stored.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameResult {
+    pub outcome: Outcome,
+    // moves played by both players together
+    pub plies: usize,
+}
+
+impl GameResult {
+    pub fn winner(&self) -> Option<u8> {
+        match self.outcome {
+            Outcome::Win { player, .. } => Some(player),
+            Outcome::Draw { .. } => None,
+        }
+    }
+}
+
+// MorrisCache holds the MorrisGame of an OngoingGame between queries.
+// apply and undo keep it in step; it is rebuilt when the game was changed through its fields.
+#[derive(Default)]
+pub struct MorrisCache(Mutex<Option<MorrisGame>>);
+
+impl MorrisCache {
+    fn lock(&self) -> MutexGuard<'_, Option<MorrisGame>> {
+        // the cache is checked before every use, so a poisoned one is still fine to read
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn get_mut(&mut self) -> Option<&mut MorrisGame> {
+        self.0
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+    }
+}
+
+impl Clone for MorrisCache {
+    fn clone(&self) -> MorrisCache {
+        MorrisCache(Mutex::new(self.lock().clone()))
+    }
+}
+
+impl fmt::Debug for MorrisCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MorrisCache")
+    }
+}
+
+impl OngoingGame {
+    pub fn remaining(&self, piece: u8) -> u8 {
+        match piece {
+            1 => self.player_one_remaining,
+            _ => self.player_two_remaining,
+        }
+    }
+
+    fn remaining_mut(&mut self, piece: u8) -> &mut u8 {
+        match piece {
+            1 => &mut self.player_one_remaining,
+            _ => &mut self.player_two_remaining,
+        }
+    }
+
+    pub(crate) fn with_morris<T>(&self, f: impl FnOnce(&MorrisGame) -> T) -> T {
+        // run f on the cached MorrisGame, rebuilt first if it no longer fits the game
+        let mut cache = self.morris.lock();
+        let morris = match cache.take() {
+            Some(morris) if self.is_cached(&morris) => morris,
+            _ => self.to_morris(),
+        };
+        f(cache.insert(morris))
+    }
+
+    fn is_cached(&self, morris: &MorrisGame) -> bool {
+        // cheap check that nobody changed the position or history behind the cache
+        morris.turn == self.turn
+            && morris.in_hand == [self.player_one_remaining, self.player_two_remaining]
+            && morris.cells == self.cells()
+            && morris.moves.len() == self.moves.len()
+            && morris.moves.last().copied()
+                == self.moves.last().and_then(|m| m.to_point_move().ok())
+            && morris.rules.move_limit == self.move_limit
+            && morris.rules.name == self.variant.rules().name
+    }
+
+    pub fn to_morris(&self) -> MorrisGame {
+        // the same position as a game on the 3x3 board of the variant, so the generic engine decides
+        let mut rules = self.variant.rules();
+        if rules.move_limit != self.move_limit {
+            // the preset stays shared, only this game gets its own copy
+            Arc::make_mut(&mut rules).move_limit = self.move_limit;
+        }
+        MorrisGame {
+            cells: self.cells().to_vec(),
+            turn: self.turn,
+            in_hand: [self.player_one_remaining, self.player_two_remaining],
+            // moves in history went through validate, so they always convert
+            moves: self.moves.iter().map(|m| m.to_point_move().unwrap()).collect(),
+            rules,
+        }
+    }
+
+    pub fn zobrist(&self) -> u64 {
+        // 64 bit key of board, player to move and pieces in hand
+        Position::from_game(self).hash()
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        // all moves the player to move can make, placements first if pieces are left in hand.
+        // nothing is legal once somebody has three in a row, resigned or ran out of time.
+        if self.ending.is_some() {
+            return Vec::new();
+        }
+        self.with_morris(|morris| morris.legal_moves())
+            .iter()
+            .map(|m| Move::from_point_move(m).unwrap())
+            .collect()
+    }
+
+    pub fn validate(&self, new_move: &Move) -> Result<(), GameError> {
+        // Err if the move cannot be played by the player to move.
+        // errors come back with board coordinates instead of point names.
+        if self.ending.is_some() {
+            return Err(GameError::GameOver);
+        }
+        let point_move = new_move.to_point_move()?;
+        self.with_morris(|morris| morris.validate(&point_move))
+            .map_err(grid_error)
+    }
+
+    pub fn apply(&mut self, new_move: Move) -> Result<(), GameError> {
+        // validate and play the move for the player to move, then pass the turn.
+        self.validate(&new_move)?;
+        let point_move = new_move.to_point_move()?;
+        let (row, col, new_row, new_col) = new_move.as_coord()?;
+        let piece = self.turn;
+        match (new_row, new_col) {
+            (Some(new_row), Some(new_col)) => {
+                self.board[row][col] = 0;
+                self.board[new_row][new_col] = piece;
+            }
+            _ => {
+                self.board[row][col] = piece;
+                *self.remaining_mut(piece) -= 1;
+            }
+        }
+        self.moves.push(new_move);
+        self.update_turn();
+        // validate left the position before the move in the cache
+        if let Some(morris) = self.morris.get_mut() {
+            morris.apply_unchecked(point_move);
+        }
+        Ok(())
+    }
+
+    pub fn apply_as(&mut self, player_id: &str, new_move: Move) -> Result<(), GameError> {
+        // same as apply, for callers that know who sent the move (server, bots).
+        if self.player_one != player_id && self.player_two != player_id {
+            return Err(GameError::PlayerNotInGame {
+                player_id: player_id.to_string(),
+            });
+        }
+        if self.whose_turn != player_id {
+            return Err(GameError::NotYourTurn);
+        }
+        self.apply(new_move)
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        // take back the last move and give the turn back to whoever played it.
+        let last_move = self.moves.pop()?;
+        // a resignation or time out ends the game after the last move, taking it back resumes play
+        self.ending = None;
+        self.update_turn();
+        let piece = self.turn;
+        // moves in history went through validate, so they always convert
+        let (row, col, new_row, new_col) = last_move.as_coord().unwrap();
+        match (new_row, new_col) {
+            (Some(new_row), Some(new_col)) => {
+                self.board[new_row][new_col] = 0;
+                self.board[row][col] = piece;
+            }
+            _ => {
+                self.board[row][col] = 0;
+                *self.remaining_mut(piece) += 1;
+            }
+        }
+        if let Some(morris) = self.morris.get_mut() {
+            morris.undo();
+        }
+        Some(last_move)
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        // None while the game goes on.
+        if let Some(ending) = self.ending {
+            return Some(ending.outcome);
+        }
+        self.with_morris(|morris| morris.outcome())
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        // outcome with the number of moves it took, None while the game goes on.
+        if let Some(ending) = self.ending {
+            return Some(ending);
+        }
+        let outcome = self.outcome()?;
+        Some(GameResult {
+            outcome,
+            plies: self.moves.len(),
+        })
+    }
+
+    pub fn resign(&mut self, player_id: &str) -> Result<GameResult, GameError> {
+        self.end_for(player_id, WinReason::Resignation)
+    }
+
+    pub fn time_out(&mut self, player_id: &str) -> Result<GameResult, GameError> {
+        self.end_for(player_id, WinReason::Timeout)
+    }
+
+    fn end_for(&mut self, player_id: &str, reason: WinReason) -> Result<GameResult, GameError> {
+        // player_id loses the game for a reason outside the board
+        let loser = match player_id {
+            id if id == self.player_one => 1,
+            id if id == self.player_two => 2,
+            _ => {
+                return Err(GameError::PlayerNotInGame {
+                    player_id: player_id.to_string(),
+                })
+            }
+        };
+        if self.outcome().is_some() {
+            return Err(GameError::GameOver);
+        }
+        let result = GameResult {
+            outcome: Outcome::Win {
+                player: opponent(loser),
+                reason,
+            },
+            plies: self.moves.len(),
+        };
+        self.ending = Some(result);
+        Ok(result)
+    }
+}
+
+impl Move {
+    pub fn to_point_move(&self) -> Result<MorrisMove, GameError> {
+        // point indices on the three men's board are row * 3 + col
+        let (row, col, new_row, new_col) = self.as_coord()?;
+        match (self.new_col, new_row, new_col) {
+            (None, _, _) => Ok(MorrisMove::place(row * 3 + col)),
+            (Some(_), Some(new_row), Some(new_col)) => {
+                Ok(MorrisMove::slide(row * 3 + col, new_row * 3 + new_col))
+            }
+            _ => Err(GameError::InvalidNotation {
+                input: self.print(),
+                position: 4,
+            }),
+        }
+    }
+
+    pub fn from_point_move(point_move: &MorrisMove) -> Result<Move, GameError> {
+        let to = (point_move.to / 3, point_move.to % 3);
+        match point_move.from {
+            None => Move::coord_to_new_move(&to.0, &to.1),
+            Some(from) => Move::coord_to_move(&(from / 3), &(from % 3), &to.0, &to.1),
+        }
+    }
+}
+
+pub fn opponent(player: u8) -> u8 {
+    match player {
+        1 => 2,
+        _ => 1,
+    }
+}
+
+fn grid_error(error: GameError) -> GameError {
+    // point errors of the three men's board as (row, col) errors
+    let coord = |point: &str| match Board::three_mens().point_index(point) {
+        Some(index) => (index / 3, index % 3),
+        None => (0, 0),
+    };
+    match error {
+        GameError::PointOccupied { point } => {
+            let (row, col) = coord(&point);
+            GameError::PlaceOccupied { row, col }
+        }
+        GameError::NoPieceOnPoint { point } => {
+            let (row, col) = coord(&point);
+            GameError::NoPieceToMove { row, col }
+        }
+        GameError::PointNotOwned { player, point } => {
+            let (row, col) = coord(&point);
+            GameError::IncorrectOwnership { player, row, col }
+        }
+        GameError::NotConnected { from, to } => {
+            let ((row, col), (new_row, new_col)) = (coord(&from), coord(&to));
+            GameError::NotAdjacent {
+                row,
+                col,
+                new_row,
+                new_col,
+            }
+        }
+        other => other,
+    }
+}
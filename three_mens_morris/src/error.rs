@@ -0,0 +1,265 @@
+use std::fmt;
+
+// GameError is returned by every public rules function.
+// players are identified by their piece (1 or 2) and squares by board coordinates (row, col).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    // move related errors
+    NotYourTurn,
+    GameOver,
+    PlaceOccupied {
+        row: usize,
+        col: usize,
+    },
+    MaxPiecePlayed {
+        player: u8,
+    },
+    NoPieceToMove {
+        row: usize,
+        col: usize,
+    },
+    IncorrectOwnership {
+        player: u8,
+        row: usize,
+        col: usize,
+    },
+    NotAdjacent {
+        row: usize,
+        col: usize,
+        new_row: usize,
+        new_col: usize,
+    },
+    NotPlayedAllPieces {
+        player: u8,
+    },
+    NoLegalMove {
+        player: u8,
+    },
+    PlayerNotInGame {
+        player_id: String,
+    },
+    // point related errors, for games on any Board. points are given by name.
+    UnknownPoint {
+        name: String,
+    },
+    PointOccupied {
+        point: String,
+    },
+    NoPieceOnPoint {
+        point: String,
+    },
+    PointNotOwned {
+        player: u8,
+        point: String,
+    },
+    NotConnected {
+        from: String,
+        to: String,
+    },
+    RemovalRequired,
+    RemovalNotAllowed,
+    CannotRemove {
+        point: String,
+    },
+    // board related errors
+    UnknownVariant {
+        name: String,
+    },
+    UnsupportedVariant {
+        name: String,
+    },
+    // no bot of that name or difficulty
+    UnknownBot {
+        name: String,
+    },
+    // custom board definitions
+    InvalidDefinition {
+        reason: String,
+    },
+    DuplicatePoint {
+        name: String,
+    },
+    DisconnectedBoard {
+        point: String,
+    },
+    OutOfBoard {
+        row: usize,
+        col: usize,
+    },
+    InvalidPiece {
+        row: usize,
+        col: usize,
+        piece: u8,
+    },
+    // input related errors
+    InvalidNotation {
+        input: String,
+        // character offset where parsing failed
+        position: usize,
+    },
+    InvalidFen {
+        fen: String,
+        reason: String,
+    },
+    InvalidTablebase {
+        reason: String,
+    },
+    InvalidBook {
+        reason: String,
+    },
+    InvalidNetwork {
+        reason: String,
+    },
+    // game records
+    InvalidRecord {
+        // 1-based line of the record
+        line: usize,
+        reason: String,
+    },
+    IllegalRecordMove {
+        // 1-based number of the move in the game, counting both players
+        ply: usize,
+        error: Box<GameError>,
+    },
+    RecordResultMismatch {
+        // result and reason, e.g. "1-0 three_in_a_row" or "*"
+        recorded: String,
+        played: String,
+    },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::NotYourTurn => {
+                write!(f, "It is not your turn.")
+            }
+            GameError::GameOver => {
+                write!(f, "The game is already over!")
+            }
+            GameError::PlaceOccupied { row, col } => {
+                write!(f, "({}, {}) already occupied!", row, col)
+            }
+            GameError::MaxPiecePlayed { player } => {
+                write!(f, "Player {} already placed all pieces!", player)
+            }
+            GameError::NoPieceToMove { row, col } => {
+                write!(f, "({}, {}) has no piece to move!", row, col)
+            }
+            GameError::IncorrectOwnership { player, row, col } => {
+                write!(
+                    f,
+                    "Piece on ({}, {}) does not belong to player {}!",
+                    row, col, player
+                )
+            }
+            GameError::NotAdjacent {
+                row,
+                col,
+                new_row,
+                new_col,
+            } => {
+                write!(
+                    f,
+                    "({}, {}) is not connected to ({}, {})!",
+                    row, col, new_row, new_col
+                )
+            }
+            GameError::NotPlayedAllPieces { player } => {
+                write!(
+                    f,
+                    "Player {} must play remaining pieces to start moving existing pieces!",
+                    player
+                )
+            }
+            GameError::NoLegalMove { player } => {
+                write!(f, "Player {} has no legal move.", player)
+            }
+            GameError::PlayerNotInGame { player_id } => {
+                write!(f, "Player {} is not in this game.", player_id)
+            }
+            GameError::UnknownPoint { name } => {
+                write!(f, "There is no point named {} on this board!", name)
+            }
+            GameError::PointOccupied { point } => {
+                write!(f, "{} already occupied!", point)
+            }
+            GameError::NoPieceOnPoint { point } => {
+                write!(f, "{} has no piece to move!", point)
+            }
+            GameError::PointNotOwned { player, point } => {
+                write!(f, "Piece on {} does not belong to player {}!", point, player)
+            }
+            GameError::NotConnected { from, to } => {
+                write!(f, "{} is not connected to {}!", from, to)
+            }
+            GameError::RemovalRequired => {
+                write!(f, "A mill was formed, an opponent piece must be removed!")
+            }
+            GameError::RemovalNotAllowed => {
+                write!(f, "No mill was formed, nothing can be removed!")
+            }
+            GameError::CannotRemove { point } => {
+                write!(f, "Piece on {} cannot be removed!", point)
+            }
+            GameError::UnknownVariant { name } => {
+                write!(f, "There is no game called {}!", name)
+            }
+            GameError::UnsupportedVariant { name } => {
+                write!(f, "{} is not played on the 3x3 board!", name)
+            }
+            GameError::UnknownBot { name } => {
+                write!(f, "There is no bot called {}!", name)
+            }
+            GameError::InvalidDefinition { reason } => {
+                write!(f, "Invalid board definition: {}", reason)
+            }
+            GameError::DuplicatePoint { name } => {
+                write!(f, "Point {} is defined more than once!", name)
+            }
+            GameError::DisconnectedBoard { point } => {
+                write!(f, "{} cannot be reached from the rest of the board!", point)
+            }
+            GameError::OutOfBoard { row, col } => {
+                write!(f, "({}, {}) is outside of the board!", row, col)
+            }
+            GameError::InvalidPiece { row, col, piece } => {
+                write!(f, "({}, {}) holds unknown piece {}!", row, col, piece)
+            }
+            GameError::InvalidNotation { input, position } => {
+                write!(
+                    f,
+                    "Cannot read move \"{}\" at character {}.",
+                    input, position
+                )
+            }
+            GameError::InvalidFen { fen, reason } => {
+                write!(f, "Cannot read position \"{}\": {}.", fen, reason)
+            }
+            GameError::InvalidTablebase { reason } => {
+                write!(f, "Cannot use tablebase: {}.", reason)
+            }
+            GameError::InvalidBook { reason } => {
+                write!(f, "Cannot use opening book: {}.", reason)
+            }
+            GameError::InvalidNetwork { reason } => {
+                write!(f, "Cannot use network weights: {}.", reason)
+            }
+            GameError::InvalidRecord { line, reason } => {
+                write!(f, "Game record line {}: {}.", line, reason)
+            }
+            GameError::IllegalRecordMove { ply, error } => {
+                write!(f, "Game record move {}: {}", ply, error)
+            }
+            GameError::RecordResultMismatch { recorded, played } => {
+                write!(
+                    f,
+                    "Game record says {} but the moves end in {}.",
+                    recorded, played
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
@@ -0,0 +1,218 @@
+use crate::error::GameError;
+use crate::position::Position;
+use crate::rules::Variant;
+use crate::types::OngoingGame;
+
+// FEN-like position string of a 3x3 game, for bug reports, test fixtures and
+// games that start from a given position.
+//
+// o../.x./... 1 3 3 achi 0 2
+//
+// Fields, separated by spaces:
+// - the board, rows 1 to 3 separated by "/", columns a to c.
+//   "o" is a piece of player one, "x" of player two, "." an empty point (as drawn by the CLI).
+// - player to move, 1 or 2
+// - pieces player one still has in hand
+// - pieces player two still has in hand
+// - optional: variant key, three men's morris when missing
+// - optional, together: plies since the last placement (as counted by the move limit)
+//   and plies played since the start
+// - optional, last: "limit=N" to draw after N quiet plies or "limit=none",
+//   the limit of the variant when missing
+// The counters are checked against the position but not kept:
+// an OngoingGame counts plies from its own move history.
+// to_fen leaves them out when that history does not start from the empty board.
+
+impl OngoingGame {
+    pub fn to_fen(&self) -> String {
+        let rows: Vec<String> = self
+            .board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&piece| match piece {
+                        1 => 'o',
+                        2 => 'x',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut fen = format!(
+            "{} {} {} {}",
+            rows.join("/"),
+            self.turn,
+            self.player_one_remaining,
+            self.player_two_remaining
+        );
+        if self.variant != Variant::ThreeMens {
+            fen.push(' ');
+            fen.push_str(self.variant.name());
+        }
+        let placed = self.cells().iter().filter(|&&piece| piece != 0).count();
+        let placements = self.moves.iter().filter(|m| m.new_col.is_none()).count();
+        if placements == placed {
+            fen.push_str(&format!(
+                " {} {}",
+                self.with_morris(|morris| morris.quiet_moves()),
+                self.moves.len()
+            ));
+        }
+        if self.move_limit != self.variant.rules().move_limit {
+            match self.move_limit {
+                Some(limit) => fen.push_str(&format!(" limit={}", limit)),
+                None => fen.push_str(" limit=none"),
+            }
+        }
+        fen
+    }
+
+    pub fn from_fen(fen: &str) -> Result<OngoingGame, GameError> {
+        // a game in the given position, players and move history are left empty
+        OngoingGame::read_fen(fen, None)
+    }
+
+    pub fn from_fen_with_variant(fen: &str, variant: Variant) -> Result<OngoingGame, GameError> {
+        // from_fen for a game of the given variant: a string without a variant key is read
+        // as that variant, one naming another variant is refused
+        OngoingGame::read_fen(fen, Some(variant))
+    }
+
+    fn read_fen(fen: &str, expected: Option<Variant>) -> Result<OngoingGame, GameError> {
+        let invalid = |reason: String| GameError::InvalidFen {
+            fen: fen.to_string(),
+            reason,
+        };
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        let move_limit = match fields.last().and_then(|field| field.strip_prefix("limit=")) {
+            Some(limit) => {
+                fields.pop();
+                match limit {
+                    "none" => Some(None),
+                    _ => Some(Some(limit.parse().map_err(|_| {
+                        invalid(format!(
+                            "move limit must be a number or none, not {}",
+                            limit
+                        ))
+                    })?)),
+                }
+            }
+            None => None,
+        };
+        let named = match fields.get(4) {
+            Some(name) if !name.starts_with(|c: char| c.is_ascii_digit()) => {
+                let variant =
+                    Variant::from_name(name).ok_or(invalid(format!("unknown variant {}", name)))?;
+                fields.remove(4);
+                Some(variant)
+            }
+            _ => None,
+        };
+        let variant = match (named, expected) {
+            (Some(named), Some(expected)) if named != expected => {
+                return Err(invalid(format!(
+                    "position is for {} but the game is {}",
+                    named.name(),
+                    expected.name()
+                )))
+            }
+            (Some(variant), _) | (None, Some(variant)) => variant,
+            (None, None) => Variant::ThreeMens,
+        };
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err(invalid(format!(
+                "expected board, player to move, pieces in hand and two optional counters, found {} fields",
+                fields.len()
+            )));
+        }
+        let mut game = OngoingGame::with_variant(variant)?;
+        if let Some(move_limit) = move_limit {
+            game.move_limit = move_limit;
+        }
+
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != 3 {
+            return Err(invalid(format!("expected 3 rows, found {}", rows.len())));
+        }
+        let mut on_board = [0, 0];
+        for (row, cells) in rows.iter().enumerate() {
+            if cells.chars().count() != 3 {
+                return Err(invalid(format!("row {} needs 3 points", row + 1)));
+            }
+            for (col, c) in cells.chars().enumerate() {
+                game.board[row][col] = match c {
+                    'o' => 1,
+                    'x' => 2,
+                    '.' => 0,
+                    _ => return Err(invalid(format!("unknown piece {} in row {}", c, row + 1))),
+                };
+                if game.board[row][col] != 0 {
+                    on_board[game.board[row][col] as usize - 1] += 1;
+                }
+            }
+        }
+
+        game.turn = match fields[1] {
+            "1" => 1,
+            "2" => 2,
+            turn => {
+                return Err(invalid(format!(
+                    "player to move must be 1 or 2, not {}",
+                    turn
+                )))
+            }
+        };
+        let number = |field: &str, name: &str| -> Result<usize, GameError> {
+            field
+                .parse()
+                .map_err(|_| invalid(format!("{} must be a number, not {}", name, field)))
+        };
+        let in_hand = [
+            number(fields[2], "pieces in hand")?,
+            number(fields[3], "pieces in hand")?,
+        ];
+        let pieces = variant.rules().pieces as usize;
+        if in_hand[0] > pieces || in_hand[1] > pieces {
+            return Err(invalid(format!(
+                "{} has {} pieces, not {} and {} in hand",
+                variant.name(),
+                pieces,
+                in_hand[0],
+                in_hand[1]
+            )));
+        }
+        game.player_one_remaining = in_hand[0] as u8;
+        game.player_two_remaining = in_hand[1] as u8;
+        // the position itself has to make sense, see legality.rs.
+        // whether a game gets there is not searched.
+        if let Some(illegality) = Position::from_game(&game).illegalities().first() {
+            return Err(invalid(illegality.to_string()));
+        }
+
+        if fields.len() > 4 {
+            let quiet = number(fields[4], "plies since the last placement")?;
+            let plies = number(fields[5], "plies played")?;
+            let placed = on_board[0] + on_board[1];
+            if plies < placed {
+                return Err(invalid(format!(
+                    "{} plies cannot place {} pieces",
+                    plies, placed
+                )));
+            }
+            if plies % 2 != game.turn as usize - 1 {
+                return Err(invalid(format!(
+                    "player {} cannot be to move after {} plies",
+                    game.turn, plies
+                )));
+            }
+            let placing = in_hand[0] > 0 || in_hand[1] > 0;
+            if quiet > plies - placed || (placing && quiet > 0) {
+                return Err(invalid(format!(
+                    "{} plies since the last placement do not fit {} plies with {} placements",
+                    quiet, plies, placed
+                )));
+            }
+        }
+        Ok(game)
+    }
+}
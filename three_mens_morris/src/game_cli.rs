@@ -1,104 +1,36 @@
 use colored::*;
-use std::fmt;
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use three_mens_morris::referee::Referee;
+use rand::Rng;
 
+use three_mens_morris::bot::{Bot, BotFiles, Registry};
+use three_mens_morris::engine::Outcome;
+use three_mens_morris::error::GameError;
+use three_mens_morris::record::{self, GameRecord};
+use three_mens_morris::types::{Move, OngoingGame};
 
-#[derive(Debug)]
-pub enum GameError {
-    // move related errors
-    DuplicatePlay {
-        player: char,
-    },
-    PlaceOccupied {
-        row: usize,
-        col: usize,
-    },
-    MaxPiecePlayed {
-        player: char,
-    },
-    NoPieceToMove {
-        row: usize,
-        col: usize,
-    },
-    IncorrectOwnership {
-        player: char,
-        row: usize,
-        col: usize,
-    },
-    InvalidMove {},
-    NotPlayedAllPieces {
-        player: char,
-    },
-    CustomError {
-        message: &'static str,
-    },
-    // input related errors
-}
-
-impl fmt::Display for GameError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            GameError::DuplicatePlay { player } => {
-                write!(f, "Player {} cannot play twice in a row.", player)
-            }
-            GameError::PlaceOccupied { row, col } => {
-                write!(f, "({}, {}) already occupied!", row, col)
-            }
-            GameError::MaxPiecePlayed { player } => {
-                write!(f, "Player {} already placed all 3 pieces!", player)
-            }
-            GameError::NoPieceToMove { row, col } => {
-                write!(f, "({}, {}) has no piece to move!", row, col)
-            }
-            GameError::IncorrectOwnership { player, row, col } => {
-                write!(
-                    f,
-                    "Piece on ({}, {}) does not belong to player {}!",
-                    row, col, player
-                )
-            }
-            GameError::InvalidMove {} => {
-                write!(f, "The move is not valid!")
-            }
-            GameError::NotPlayedAllPieces { player } => {
-                write!(
-                    f,
-                    "Player {} must play remaining pieces to start moving existing pieces!",
-                    player
-                )
-            }
-            GameError::CustomError { message } => {
-                write!(f, "Custom error: {}", message)
-            }
-        }
-    }
-}
-
-impl std::error::Error for GameError {}
 
 pub struct Game {
-    // column, and row
-    moves: Vec<Move>,
-    state_history: Vec<State>,
-    current_state: State,
-    winner: char,
+    // every state before each move, written out by write_history
+    state_history: Vec<OngoingGame>,
+    current_state: OngoingGame,
     player_mode: u8,
-    bot: StupidBot,
+    registry: Registry,
+    bot: Box<dyn Bot>,
 }
 
 impl Game {
     pub fn new() -> Game {
+        let registry = Registry::with_files(&BotFiles::from_env());
+        let bot = registry.find("random").expect("random bot is registered");
         Game {
-            moves: Vec::new(),
             state_history: Vec::new(),
-            current_state: State::new(),
-            winner: ' ',
+            current_state: OngoingGame::new(),
             player_mode: 2,
-            bot: StupidBot::new(),
+            registry,
+            bot,
         }
     }
 
@@ -117,6 +49,9 @@ impl Game {
         for state in &self.state_history {
             writeln!(file, "{}", state.get_state()).expect("Corrupted history.");
         }
+        // the same game as a record other tools can replay
+        let record = GameRecord::from_game(&self.current_state, &record::date(timestamp));
+        fs::write(format!("{}.pgn", timestamp), record.to_pgn()).expect("Unable to write record.");
     }
 
     pub fn get_player_mode(&mut self) -> Result<(), ()> {
@@ -139,27 +74,34 @@ impl Game {
             1 => {
                 self.player_mode = mode;
                 println!("Single player mode selected.");
-                println!("Fight against stupid bot.");
-                // 1: player goes first, 2: bot goes first
+                self.get_bot();
+                println!("Fight against {} bot.", self.bot.name());
+                // 1: bot goes first, 2: player goes first
                 let mut rng = rand::thread_rng();
                 let number: u8 = rng.gen_range(1..=2);
                 match number {
                     1 => {
-                        self.bot.set_player(1);
+                        self.current_state.player_one = self.bot.id().to_string();
+                        self.current_state.player_two = String::from("player");
                         println!("Bot is set to player one.");
                     }
                     2 => {
-                        self.bot.set_player(2);
+                        self.current_state.player_one = String::from("player");
+                        self.current_state.player_two = self.bot.id().to_string();
                         println!("Bot is set to player two.");
                     }
                     _ => {
                         return Err(());
                     }
                 }
+                self.current_state.whose_turn = self.current_state.player_one.clone();
                 return Ok(());
             }
             2 => {
                 self.player_mode = mode;
+                self.current_state.player_one = String::from("o");
+                self.current_state.player_two = String::from("x");
+                self.current_state.whose_turn = self.current_state.player_one.clone();
                 println!("Multi player mode selected.");
                 return Ok(());
             }
@@ -170,23 +112,38 @@ impl Game {
         }
     }
 
-    fn multi_player(&mut self) -> u8 {
-        // loop until there is a winner
+    fn get_bot(&mut self) {
+        // a bot name or a difficulty, the random bot when empty or unknown
+        let mut input = String::new();
+        println!(
+            "Choose a bot ({}) or a difficulty (easy, medium, hard):",
+            self.registry.names().join(", ")
+        );
+        std::io::stdin().read_line(&mut input).unwrap();
+        let name = match input.trim() {
+            "" => "random",
+            name => name,
+        };
+        match self.registry.find(name) {
+            Ok(bot) => self.bot = bot,
+            Err(e) => println!("{} Playing against the random bot.", e),
+        }
+    }
+
+    fn multi_player(&mut self) -> Option<Outcome> {
+        // loop until the game is over, None if the user quits
         loop {
             // get input
             let new_move = match self.get_user_input() {
                 Ok(ok_move) => ok_move,
-                Err(()) => return 0,
+                Err(()) => return None,
             };
             // register input and move on if move is valid
-            match self.validate_move(&new_move) {
+            match self.register_move(new_move) {
                 Ok(()) => {
-                    self.register_move(&new_move);
-                    let winner = self.check_win();
-                    if winner != 0 {
-                        return winner;
+                    if let Some(outcome) = self.check_win() {
+                        return Some(outcome);
                     };
-                    self.update_next_state();
                 }
                 Err(e) => {
                     println!("{}", e);
@@ -196,43 +153,35 @@ impl Game {
         }
     }
 
-    fn single_player(&mut self) -> u8 {
-        // loop until there is a winner
+    fn single_player(&mut self) -> Option<Outcome> {
+        // loop until the game is over, None if the user quits
         loop {
-            let mut new_move = Move::new();
             // let bot check if it is his turn first
-            if self.bot.get_player() == self.current_state.turn {
-                // random move is already validated
-                match self.bot.make_random_move(&self.current_state) {
-                    Ok(bot_move) => {
-                        new_move = bot_move;
-                        println!(
-                            "bot move {} {} {:?} {:?}",
-                            new_move.row, new_move.col, new_move.new_row, new_move.new_col
-                        );
-                        if new_move.is_new_move() {
-                            println!("Bot has played {}{}", new_move.col, new_move.row);
-                        } else {
-                            println!(
-                                "Bot has played {}{} -> {:?}{:?}",
-                                new_move.col, new_move.row, new_move.new_col, new_move.new_row
-                            );
-                        }
+            if self.bot.id() == self.current_state.whose_turn {
+                // bot move is already validated
+                self.state_history.push(self.current_state.clone());
+                match self.bot.make_move(&mut self.current_state) {
+                    Ok(()) => {
+                        let bot_move = self.current_state.moves.last().unwrap();
+                        println!("Bot has played {}", bot_move.print());
+                        self.print_current_board();
                     }
-                    Err(()) => {
+                    Err(e) => {
+                        println!("{}", e);
                         println!("Bot had an error making a move.");
+                        self.state_history.pop();
                     }
                 }
             }
             // player register new move
             else {
                 loop {
-                    new_move = match self.get_user_input() {
+                    let new_move = match self.get_user_input() {
                         Ok(ok_move) => ok_move,
-                        Err(()) => return 0,
+                        Err(()) => return None,
                     };
                     // register input and move on if move is valid
-                    match self.validate_move(&new_move) {
+                    match self.register_move(new_move) {
                         Ok(()) => break,
                         Err(e) => {
                             println!("{}", e);
@@ -243,12 +192,9 @@ impl Game {
                 }
             }
 
-            self.register_move(&new_move);
-            let winner = self.check_win();
-            if winner != 0 {
-                return winner;
+            if let Some(outcome) = self.check_win() {
+                return Some(outcome);
             };
-            self.update_next_state();
         }
     }
 
@@ -283,52 +229,30 @@ impl Game {
 
         self.print_current_board();
 
-        let mut winner = 0;
         println!("Current state:");
         println!("{}", self.current_state.get_state());
-        match self.player_mode {
-            1 => {
-                winner = self.single_player();
-            }
-            2 => {
-                winner = self.multi_player();
-            }
-            _ => (),
+        let outcome = match self.player_mode {
+            1 => self.single_player(),
+            2 => self.multi_player(),
+            _ => None,
+        };
+        match outcome {
+            Some(Outcome::Win { player, .. }) => println!("Player {} is the winner!", player),
+            Some(Outcome::Draw { reason }) => println!("The game is a draw ({:?}).", reason),
+            None => println!("Game stopped."),
         }
-        println!("Player {} is the winner!", winner);
         self.print_current_board();
         self.print_move_history();
     }
 
     pub fn print_move_history(&self) {
         println!("Game history:");
-        for (i, current_move) in self.moves.iter().enumerate() {
-            let mut player;
-            match i % 2 {
-                0 => {
-                    player = 'o';
-                }
-                1 => {
-                    player = 'x';
-                }
-                _ => {
-                    // TODO: handle error
-                    player = ' ';
-                }
-            }
-
-            if current_move.new_row.is_none() {
-                println!("{}: {}{} ", player, current_move.col, current_move.row);
-            } else {
-                println!(
-                    "{}: {}{}->{}{}",
-                    player,
-                    current_move.col,
-                    current_move.row,
-                    current_move.new_col.unwrap(),
-                    current_move.new_row.unwrap()
-                );
-            }
+        for (i, current_move) in self.current_state.moves.iter().enumerate() {
+            let player = match i % 2 {
+                0 => 'o',
+                _ => 'x',
+            };
+            println!("{}: {}", player, current_move.print());
         }
     }
 
@@ -359,163 +283,24 @@ impl Game {
         }
     }
 
-    pub fn register_move(&mut self, _move: &Move) {
-        // before updating current state, save it to history
-        self.state_history.push(self.current_state.clone());
-        // apply on current board first
-        let (row_coord, col_coord) = _move.new_as_coord();
-        // new piece
-        if _move.is_new_move() {
-            self.current_state.board[row_coord][col_coord] = self.current_state.turn;
-        }
-        // moving piece
-        else {
-            self.current_state.board[row_coord][col_coord] = 0;
-            let (new_row_coord, new_col_coord) = _move.move_as_coord();
-            self.current_state.board[new_row_coord][new_col_coord] = self.current_state.turn;
-        }
-
-        match self.current_state.turn {
-            1 => {
-                if self.current_state.player_one_remaining > 0 {
-                    self.current_state.player_one_remaining -= 1
-                };
-            }
-            2 => {
-                if self.current_state.player_two_remaining > 0 {
-                    self.current_state.player_two_remaining -= 1
-                };
-            }
-            _ => (),
-        }
+    pub fn register_move(&mut self, new_move: Move) -> Result<(), GameError> {
+        // engine validates and applies the move. state is saved to history only if it went through.
+        let previous_state = self.current_state.clone();
+        self.current_state.apply(new_move)?;
+        self.state_history.push(previous_state);
 
         println!("Move has been registered:");
-        // record Move to move history
-        self.moves.push(_move.clone());
         self.print_current_board();
+        Ok(())
     }
 
-    pub fn update_next_state(&mut self) {
-        match self.current_state.turn {
-            1 => {
-                self.current_state.turn = 2;
-            }
-            2 => {
-                self.current_state.turn = 1;
-            }
-            _ => (),
-        }
-    }
-
-    pub fn check_win(&self) -> u8 {
-        let board = &self.current_state.board;
-        let mut game_over = false;
-        for i in 0..3 {
-            if (board[i][0] != 0 && board[i][0] == board[i][1] && board[i][0] == board[i][2])
-                || (board[0][i] != 0 && board[0][i] == board[1][i] && board[0][i] == board[2][i])
-            {
-                self.write_history();
-                game_over = true;
-            }
-        }
-
-        // Check diagonals
-        if (board[0][0] != 0 && board[0][0] == board[1][1] && board[0][0] == board[2][2])
-            || (board[0][2] != 0 && board[0][2] == board[1][1] && board[0][2] == board[2][0])
-        {
+    pub fn check_win(&self) -> Option<Outcome> {
+        // game is over on a win or a draw, history is written once either happens
+        let outcome = self.current_state.outcome();
+        if outcome.is_some() {
             self.write_history();
-            game_over = true;
         }
-
-        if game_over {
-            return self.current_state.turn;
-        } else {
-            return 0;
-        };
-    }
-
-    pub fn validate_move(&self, _move: &Move) -> Result<(), GameError> {
-        // validate_move takes Move and outputs error if invalid.
-        let (row_coord, col_coord) = _move.new_as_coord();
-        let current_player = match self.current_state.turn {
-            1 => 'o',
-            2 => 'x',
-            _ => ' ',
-        };
-
-        // placing new piece
-        if _move.is_new_move() {
-            // check if place already has a piece
-            if self.current_state.board[row_coord][col_coord] != 0 {
-                return Err(GameError::PlaceOccupied {
-                    row: row_coord,
-                    col: col_coord,
-                });
-            }
-            match self.current_state.turn {
-                1 => {
-                    if self.current_state.player_one_remaining == 0 {
-                        return Err(GameError::MaxPiecePlayed {
-                            player: current_player,
-                        });
-                    }
-                }
-                2 => {
-                    if self.current_state.player_two_remaining == 0 {
-                        return Err(GameError::MaxPiecePlayed {
-                            player: current_player,
-                        });
-                    }
-                }
-                _ => {
-                    return Err(GameError::CustomError {
-                        message: "unknown turn.",
-                    })
-                }
-            }
-            return Ok(());
-        }
-
-        // moving existing piece
-        // validate if piece exists to move
-        if self.current_state.board[row_coord][col_coord] == 0 {
-            return Err(GameError::NoPieceToMove {
-                row: row_coord,
-                col: col_coord,
-            });
-        }
-        // validate if all remaining pieces are played.
-        if self.current_state.player_one_remaining > 0
-            || self.current_state.player_two_remaining > 0
-        {
-            return Err(GameError::NotPlayedAllPieces {
-                player: current_player,
-            });
-        }
-        // validate if initial position belongs to player
-        if self.current_state.turn != self.current_state.board[row_coord][col_coord] {
-            return Err(GameError::IncorrectOwnership {
-                player: current_player,
-                row: row_coord,
-                col: col_coord,
-            });
-        }
-        // try unwrapping new row and new columns
-        // convert new col and row to cartesian coordinates
-        let (new_row_coord, new_col_coord) = _move.move_as_coord();
-        // validate if new position is not occupied
-        if self.current_state.board[new_row_coord][new_col_coord] != 0 {
-            return Err(GameError::PlaceOccupied {
-                row: new_row_coord,
-                col: new_col_coord,
-            });
-        }
-        // player can only move to connected grid.
-        if !Referee::is_valid_move(row_coord, col_coord, new_row_coord, new_col_coord) {
-            return Err(GameError::InvalidMove {});
-        }
-
-        return Ok(());
+        outcome
     }
 
     pub fn convert_str_to_row_col(&self, move_entry: &str) -> Result<(char, u8), &'static str> {
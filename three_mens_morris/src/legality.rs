@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::engine::opponent;
+use crate::morris::MorrisMove;
+use crate::position::{bits, Position};
+
+// Legality tells whether a 3x3 position could come up in a real game,
+// for positions loaded from files, the frontend or the simulator.
+// Goals:
+// - every inconsistency found by looking at the position, each with its own reason (illegalities)
+// - whether some game from the empty board ends in the position (is_reachable),
+//   decided by taking moves back until the empty board shows up
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Illegality {
+    // a point holds pieces of both players
+    SharedPoint {
+        point: usize,
+    },
+    // pieces on the board and in hand do not add up to the pieces of the variant
+    PieceCount {
+        player: u8,
+        on_board: u8,
+        in_hand: u8,
+        pieces: u8,
+    },
+    // placements alternate starting with player one, so while they go on
+    // the pieces placed tell who is to move
+    WrongTurn {
+        turn: u8,
+        placed: [u8; 2],
+    },
+    // both players have three in a row
+    BothWon,
+    // the game ended with the winner's move, so the loser is to move
+    WinnerToMove {
+        player: u8,
+    },
+    // nothing above, but no game from the empty board gets there
+    Unreachable,
+}
+
+impl fmt::Display for Illegality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Illegality::SharedPoint { point } => {
+                write!(f, "point {} holds pieces of both players", point)
+            }
+            Illegality::PieceCount {
+                player,
+                on_board,
+                in_hand,
+                pieces,
+            } => write!(
+                f,
+                "player {} has {} pieces on the board and {} in hand instead of {}",
+                player, on_board, in_hand, pieces
+            ),
+            Illegality::WrongTurn { turn, placed } => write!(
+                f,
+                "player {} cannot be to move with {} and {} pieces placed",
+                turn, placed[0], placed[1]
+            ),
+            Illegality::BothWon => write!(f, "both players have three in a row"),
+            Illegality::WinnerToMove { player } => {
+                write!(f, "player {} has three in a row and is to move", player)
+            }
+            Illegality::Unreachable => write!(f, "no game leads to this position"),
+        }
+    }
+}
+
+impl Position {
+    pub fn illegalities(&self) -> Vec<Illegality> {
+        // everything wrong with the position that shows without searching, empty if nothing
+        let mut found = Vec::new();
+        for point in bits(self.pieces[0] & self.pieces[1]) {
+            found.push(Illegality::SharedPoint { point });
+        }
+
+        let pieces = self.variant.rules().pieces;
+        let on_board = [
+            self.pieces[0].count_ones() as u8,
+            self.pieces[1].count_ones() as u8,
+        ];
+        for (player, &count) in on_board.iter().enumerate() {
+            if count as u16 + self.in_hand[player] as u16 != pieces as u16 {
+                found.push(Illegality::PieceCount {
+                    player: player as u8 + 1,
+                    on_board: count,
+                    in_hand: self.in_hand[player],
+                    pieces,
+                });
+            }
+        }
+
+        let placing = self.in_hand[0] > 0 || self.in_hand[1] > 0;
+        let ahead = if self.turn == 2 { 1 } else { 0 };
+        if self.turn != 1 && self.turn != 2 || placing && on_board[0] != on_board[1] + ahead {
+            found.push(Illegality::WrongTurn {
+                turn: self.turn,
+                placed: on_board,
+            });
+        }
+
+        let won = |player: usize| {
+            self.masks()
+                .lines
+                .iter()
+                .any(|&line| self.pieces[player] & line == line)
+        };
+        match (won(0), won(1)) {
+            (true, true) => found.push(Illegality::BothWon),
+            (true, false) if self.turn == 1 => found.push(Illegality::WinnerToMove { player: 1 }),
+            (false, true) if self.turn == 2 => found.push(Illegality::WinnerToMove { player: 2 }),
+            _ => {}
+        }
+        found
+    }
+
+    pub fn is_reachable(&self) -> bool {
+        // search backwards: take back every move that could have led here
+        // until the starting position comes up or nothing is left to take back
+        if !self.illegalities().is_empty() {
+            return false;
+        }
+        let start = Position::new(self.variant).hash();
+        let mut seen = HashSet::from([self.hash()]);
+        let mut stack = vec![*self];
+        while let Some(position) = stack.pop() {
+            if position.hash() == start {
+                return true;
+            }
+            for previous in position.predecessors() {
+                if seen.insert(previous.hash()) {
+                    stack.push(previous);
+                }
+            }
+        }
+        false
+    }
+
+    pub fn check_legal(&self) -> Result<(), Illegality> {
+        // first inconsistency found, Unreachable if only the search finds one
+        if let Some(&illegality) = self.illegalities().first() {
+            return Err(illegality);
+        }
+        match self.is_reachable() {
+            true => Ok(()),
+            false => Err(Illegality::Unreachable),
+        }
+    }
+
+    fn predecessors(&self) -> Vec<Position> {
+        // positions one move earlier: the player who just moved placed or slid one of their pieces.
+        // the game goes on in all of them, so nobody has three in a row.
+        let player = opponent(self.turn) as usize - 1;
+        let pieces = self.variant.rules().pieces;
+        let empty = self.empty();
+        let mut moves = Vec::new();
+        for to in bits(self.pieces[player]) {
+            if self.in_hand[player] < pieces {
+                moves.push(MorrisMove::place(to));
+            }
+            if self.in_hand[player] == 0 {
+                for from in bits(self.masks().adjacency[to] & empty) {
+                    moves.push(MorrisMove::slide(from, to));
+                }
+            }
+        }
+        moves
+            .into_iter()
+            .map(|last_move| {
+                let mut previous = *self;
+                previous.undo(last_move);
+                previous
+            })
+            .filter(|previous| previous.winner().is_none())
+            .collect()
+    }
+}
@@ -2,4 +2,29 @@ pub mod ui;
 pub mod referee;
 pub mod stupid_bot;
 pub mod database;
-pub mod types;
\ No newline at end of file
+pub mod types;
+pub mod board;
+pub mod engine;
+pub mod error;
+pub mod rules;
+pub mod morris;
+pub mod definition;
+pub mod position;
+pub mod zobrist;
+pub mod symmetry;
+pub mod notation;
+pub mod record;
+pub mod fen;
+pub mod legality;
+pub mod solver;
+pub mod tablebase;
+pub mod search;
+pub mod smart_bot;
+pub mod mcts;
+pub mod mcts_bot;
+pub mod book;
+pub mod book_bot;
+pub mod network;
+pub mod network_bot;
+pub mod selfplay;
+pub mod bot;
@@ -0,0 +1,262 @@
+use std::time::{Duration, Instant};
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::engine::{opponent, Outcome};
+use crate::morris::MorrisMove;
+use crate::position::Position;
+use crate::search::completes_line;
+use crate::types::{Move, OngoingGame};
+
+// Mcts is a Monte Carlo tree search over Position, the Rust side of simulator/mcts.py.
+// Goals:
+// - UCT selection, one new node per iteration, playouts to the end of the game
+// - random playouts, or heuristic ones that take wins and block lines
+// - a budget of iterations and optionally time, whichever runs out first
+// - visit counts of the root moves, and a move picked from them with a temperature
+//   like Node::select_action: 0 plays the most visited move, infinity any move
+// - the same seed gives the same moves, for tests and self-play
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playout {
+    Random,
+    // win if possible, else block the opponent's line, else random
+    Heuristic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    pub iterations: u32,
+    pub time: Option<Duration>,
+    // weight of the exploration term of UCT
+    pub exploration: f64,
+    pub playout: Playout,
+    // plies before a playout stops and counts as a draw, 3x3 games can go on forever
+    pub max_playout: u32,
+    pub temperature: f64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            iterations: 2000,
+            time: None,
+            exploration: std::f64::consts::SQRT_2,
+            playout: Playout::Heuristic,
+            max_playout: 60,
+            temperature: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveStats {
+    pub new_move: Move,
+    pub visits: u32,
+    // average result for the player making the move: 1 win, 0.5 draw, 0 loss
+    pub value: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsResult {
+    // every legal move of the root in the order of legal_moves, empty when the game is over
+    pub moves: Vec<MoveStats>,
+    pub iterations: u32,
+}
+
+struct Node {
+    position: Position,
+    parent: Option<usize>,
+    // move from the parent to this node
+    last_move: Option<MorrisMove>,
+    children: Vec<usize>,
+    untried: Vec<MorrisMove>,
+    visits: u32,
+    // sum of the results for the player who moved into this node
+    reward: f64,
+}
+
+impl Node {
+    fn new(position: Position, parent: Option<usize>, last_move: Option<MorrisMove>) -> Node {
+        Node {
+            position,
+            parent,
+            last_move,
+            children: Vec::new(),
+            // no moves once somebody has three in a row
+            untried: position.legal_moves().as_slice().to_vec(),
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+pub struct Mcts {
+    pub config: Config,
+    rng: StdRng,
+}
+
+impl Mcts {
+    pub fn new(config: Config) -> Mcts {
+        Mcts::with_seed(config, rand::random())
+    }
+
+    pub fn with_seed(config: Config, seed: u64) -> Mcts {
+        Mcts {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn search(&mut self, game: &OngoingGame) -> MctsResult {
+        self.search_position(&Position::from_game(game))
+    }
+
+    pub fn search_position(&mut self, position: &Position) -> MctsResult {
+        let deadline = self.config.time.map(|time| Instant::now() + time);
+        let mut tree = vec![Node::new(*position, None, None)];
+        let mut iterations = 0;
+        while iterations < self.config.iterations.max(1) {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            // selection: follow UCT down to a node with moves left to try or the end of the game
+            let mut node = 0;
+            while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+                node = self.best_child(&tree, node);
+            }
+            // expansion: one random untried move
+            if !tree[node].untried.is_empty() {
+                let i = self.rng.gen_range(0..tree[node].untried.len());
+                let new_move = tree[node].untried.swap_remove(i);
+                let mut child = tree[node].position;
+                child.apply(new_move);
+                tree.push(Node::new(child, Some(node), Some(new_move)));
+                let child_index = tree.len() - 1;
+                tree[node].children.push(child_index);
+                node = child_index;
+            }
+            let winner = self.playout(tree[node].position);
+            // backpropagation: each node scores the result for the player who moved into it
+            let mut current = Some(node);
+            while let Some(i) = current {
+                let mover = opponent(tree[i].position.turn);
+                tree[i].visits += 1;
+                tree[i].reward += match winner {
+                    Some(player) if player == mover => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+                current = tree[i].parent;
+            }
+            iterations += 1;
+        }
+
+        let root = &tree[0];
+        let moves = position
+            .legal_moves()
+            .iter()
+            .map(|&new_move| {
+                let child = root
+                    .children
+                    .iter()
+                    .map(|&child| &tree[child])
+                    .find(|child| child.last_move == Some(new_move));
+                let (visits, reward) = child.map_or((0, 0.0), |child| (child.visits, child.reward));
+                MoveStats {
+                    new_move: Position::to_move(&new_move),
+                    visits,
+                    value: if visits > 0 {
+                        reward / visits as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        MctsResult { moves, iterations }
+    }
+
+    pub fn select_move(&mut self, result: &MctsResult) -> Option<Move> {
+        // pick a root move by visit count, sharpened or flattened by the temperature
+        let temperature = self.config.temperature;
+        let most = result.moves.iter().map(|stats| stats.visits).max()?;
+        if temperature <= 0.0 {
+            // first of the most visited moves
+            return result
+                .moves
+                .iter()
+                .find(|stats| stats.visits == most)
+                .map(|stats| stats.new_move.clone());
+        }
+        // visits as a share of the most visited move, so a small temperature
+        // takes them towards 0 instead of overflowing
+        let weights: Vec<f64> = result
+            .moves
+            .iter()
+            .map(|stats| (stats.visits as f64 / most as f64).powf(1.0 / temperature))
+            .collect();
+        // infinite temperature, or no visits to go by: any move
+        let chosen = match WeightedIndex::new(&weights) {
+            Ok(distribution) if temperature.is_finite() => distribution.sample(&mut self.rng),
+            _ => self.rng.gen_range(0..result.moves.len().max(1)),
+        };
+        result.moves.get(chosen).map(|stats| stats.new_move.clone())
+    }
+
+    fn best_child(&self, tree: &[Node], node: usize) -> usize {
+        // children all have a visit: each was played out once when it was added
+        let log_visits = (tree[node].visits as f64).ln();
+        let uct = |child: &Node| {
+            let visits = child.visits as f64;
+            child.reward / visits + self.config.exploration * (log_visits / visits).sqrt()
+        };
+        tree[node]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| uct(&tree[a]).total_cmp(&uct(&tree[b])))
+            .unwrap()
+    }
+
+    fn playout(&mut self, mut position: Position) -> Option<u8> {
+        // winner of a game played on from position, None for a draw
+        for _ in 0..self.config.max_playout {
+            if let Some(Outcome::Win { player, .. }) = position.outcome() {
+                return Some(player);
+            }
+            let moves = position.legal_moves();
+            let new_move = match self.config.playout {
+                Playout::Random => *moves.as_slice().choose(&mut self.rng).unwrap(),
+                Playout::Heuristic => heuristic_move(&position, moves.as_slice(), &mut self.rng),
+            };
+            position.apply(new_move);
+        }
+        match position.outcome() {
+            Some(Outcome::Win { player, .. }) => Some(player),
+            _ => None,
+        }
+    }
+}
+
+fn heuristic_move(position: &Position, moves: &[MorrisMove], rng: &mut StdRng) -> MorrisMove {
+    let player = position.turn as usize - 1;
+    if let Some(&new_move) = moves
+        .iter()
+        .find(|new_move| completes_line(position, player, new_move))
+    {
+        return new_move;
+    }
+    // points where the opponent would finish a line
+    let blocks: Vec<MorrisMove> = moves
+        .iter()
+        .copied()
+        .filter(|new_move| completes_line(position, 1 - player, &MorrisMove::place(new_move.to)))
+        .collect();
+    match blocks.choose(rng) {
+        Some(&new_move) => new_move,
+        None => *moves.choose(rng).unwrap(),
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Mutex;
+
+use crate::bot::Bot;
+use crate::error::GameError;
+use crate::mcts::{Config, Mcts, MctsResult};
+use crate::types::{Move, OngoingGame};
+
+// MctsBot plays the move Mcts picks with its configured budget and temperature.
+// The search keeps its random generator between moves, so a seeded bot replays the same game.
+
+pub struct MctsBot {
+    player_id: String,
+    mcts: Mutex<Mcts>,
+}
+
+impl MctsBot {
+    pub fn new(id: &str) -> MctsBot {
+        MctsBot::with_config(id, Config::default())
+    }
+
+    pub fn with_config(id: &str, config: Config) -> MctsBot {
+        MctsBot::from_mcts(id, Mcts::new(config))
+    }
+
+    pub fn with_seed(id: &str, config: Config, seed: u64) -> MctsBot {
+        MctsBot::from_mcts(id, Mcts::with_seed(config, seed))
+    }
+
+    fn from_mcts(id: &str, mcts: Mcts) -> MctsBot {
+        MctsBot {
+            player_id: String::from(id),
+            mcts: Mutex::new(mcts),
+        }
+    }
+
+    pub fn analyse(&self, state: &OngoingGame) -> MctsResult {
+        // visit counts and values of every move
+        self.mcts.lock().unwrap().search(state)
+    }
+}
+
+impl Bot for MctsBot {
+    fn id(&self) -> &str {
+        &self.player_id
+    }
+
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn choose_move(&self, state: &OngoingGame) -> Result<Move, GameError> {
+        if state.outcome().is_some() {
+            return Err(GameError::GameOver);
+        }
+        let mut mcts = self.mcts.lock().unwrap();
+        let result = mcts.search(state);
+        mcts.select_move(&result)
+            .ok_or(GameError::NoLegalMove { player: state.turn })
+    }
+}
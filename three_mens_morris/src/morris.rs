@@ -0,0 +1,451 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::definition::BoardDefinition;
+use crate::engine::{opponent, DrawReason, GameResult, Outcome, WinReason};
+use crate::error::GameError;
+use crate::rules::{RuleSet, Variant, MIN_PIECES};
+
+// MorrisMove is a move on any Board, by point index.
+// notation uses point names: "d2" places, "d2-d3" moves, and "xa1" after either removes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MorrisMove {
+    // None when a piece is placed from hand
+    pub from: Option<usize>,
+    pub to: usize,
+    // opponent piece taken after forming a mill
+    pub remove: Option<usize>,
+}
+
+impl MorrisMove {
+    pub fn place(to: usize) -> MorrisMove {
+        MorrisMove {
+            from: None,
+            to,
+            remove: None,
+        }
+    }
+
+    pub fn slide(from: usize, to: usize) -> MorrisMove {
+        MorrisMove {
+            from: Some(from),
+            to,
+            remove: None,
+        }
+    }
+
+    pub fn removing(self, point: usize) -> MorrisMove {
+        MorrisMove {
+            remove: Some(point),
+            ..self
+        }
+    }
+
+    pub fn parse(board: &Board, input: &str) -> Result<MorrisMove, GameError> {
+        let invalid = |position: usize| GameError::InvalidNotation {
+            input: input.to_string(),
+            position,
+        };
+        let point = |name: &str, position: usize| -> Result<usize, GameError> {
+            if name.is_empty() {
+                return Err(invalid(position));
+            }
+            board.point_index(name).ok_or(GameError::UnknownPoint {
+                name: name.to_string(),
+            })
+        };
+
+        let (main, remove) = match input.find(['x', 'X']) {
+            Some(index) => (&input[..index], Some((&input[index + 1..], index + 1))),
+            None => (input, None),
+        };
+        let mut new_move = match main.find('-') {
+            Some(index) => MorrisMove::slide(
+                point(&main[..index], 0)?,
+                point(&main[index + 1..], index + 1)?,
+            ),
+            None => MorrisMove::place(point(main, 0)?),
+        };
+        if let Some((name, position)) = remove {
+            new_move.remove = Some(point(name, position)?);
+        }
+        Ok(new_move)
+    }
+
+    pub fn notation(&self, board: &Board) -> String {
+        let name = |point: usize| board.point_name(point).unwrap_or("?").to_string();
+        let mut output = String::new();
+        if let Some(from) = self.from {
+            output.push_str(&name(from));
+            output.push('-');
+        }
+        output.push_str(&name(self.to));
+        if let Some(remove) = self.remove {
+            output.push('x');
+            output.push_str(&name(remove));
+        }
+        output
+    }
+}
+
+// MorrisGame is a game of any RuleSet: three, nine men's morris and friends.
+// players are 1 and 2 like on OngoingGame, cells are indexed by the points of the board.
+#[derive(Clone, Debug)]
+pub struct MorrisGame {
+    pub rules: Arc<RuleSet>,
+    pub cells: Vec<u8>,
+    pub turn: u8,
+    pub in_hand: [u8; 2],
+    pub moves: Vec<MorrisMove>,
+}
+
+impl MorrisGame {
+    pub fn new(rules: Arc<RuleSet>) -> MorrisGame {
+        MorrisGame {
+            cells: vec![0; rules.board.len()],
+            turn: 1,
+            in_hand: [rules.pieces; 2],
+            moves: Vec::new(),
+            rules,
+        }
+    }
+
+    pub fn from_variant(variant: Variant) -> MorrisGame {
+        MorrisGame::new(variant.rules())
+    }
+
+    pub fn from_name(name: &str) -> Result<MorrisGame, GameError> {
+        // start a game by variant key, e.g. "six_mens"
+        match Variant::from_name(name) {
+            Some(variant) => Ok(MorrisGame::from_variant(variant)),
+            None => Err(GameError::UnknownVariant {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    pub fn from_definition(definition: &BoardDefinition) -> Result<MorrisGame, GameError> {
+        // game on a board loaded from a file, see definition.rs
+        Ok(MorrisGame::new(Arc::new(definition.to_rules()?)))
+    }
+
+    pub fn set_move_limit(&mut self, move_limit: Option<u32>) {
+        // changes the limit for this game only, presets stay shared
+        Arc::make_mut(&mut self.rules).move_limit = move_limit;
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.rules.board
+    }
+
+    pub fn in_hand(&self, player: u8) -> u8 {
+        self.in_hand[player as usize - 1]
+    }
+
+    pub fn on_board(&self, player: u8) -> u8 {
+        self.cells.iter().filter(|&&piece| piece == player).count() as u8
+    }
+
+    fn name(&self, point: usize) -> String {
+        match self.board().point_name(point) {
+            Some(name) => name.to_string(),
+            None => point.to_string(),
+        }
+    }
+
+    pub fn can_fly(&self, player: u8) -> bool {
+        self.rules.flying && self.in_hand(player) == 0 && self.on_board(player) == MIN_PIECES
+    }
+
+    pub fn in_mill(&self, point: usize) -> bool {
+        // true if the piece on point is part of a completed line
+        let owner = self.cells[point];
+        owner != 0
+            && self
+                .board()
+                .lines_through(point)
+                .any(|line| line.iter().all(|&p| self.cells[p] == owner))
+    }
+
+    fn closes_mill(&self, from: Option<usize>, to: usize, player: u8) -> bool {
+        // would player complete a line through `to` by moving there
+        self.board().lines_through(to).any(|line| {
+            line.iter()
+                .all(|&p| p == to || (self.cells[p] == player && Some(p) != from))
+        })
+    }
+
+    pub fn removable(&self, player: u8) -> Vec<usize> {
+        // opponent pieces player may take after a mill.
+        // pieces in a mill are safe unless every opponent piece is in one.
+        let target = opponent(player);
+        let pieces: Vec<usize> = (0..self.cells.len())
+            .filter(|&p| self.cells[p] == target)
+            .collect();
+        let free: Vec<usize> = pieces.iter().copied().filter(|&p| !self.in_mill(p)).collect();
+        match free.is_empty() {
+            true => pieces,
+            false => free,
+        }
+    }
+
+    fn destinations(&self, from: usize, player: u8) -> Vec<usize> {
+        match self.can_fly(player) {
+            true => (0..self.cells.len()).filter(|&p| self.cells[p] == 0).collect(),
+            false => self
+                .board()
+                .neighbours(from)
+                .iter()
+                .copied()
+                .filter(|&p| self.cells[p] == 0)
+                .collect(),
+        }
+    }
+
+    fn decided(&self) -> Option<Outcome> {
+        // game end that does not depend on move generation
+        if !self.rules.removal {
+            if let Some(player) = self.board().completed_line(&self.cells) {
+                return Some(Outcome::Win {
+                    player,
+                    reason: WinReason::ThreeInARow,
+                });
+            }
+        }
+        for player in [1, 2] {
+            if self.rules.removal && self.in_hand(player) + self.on_board(player) < MIN_PIECES {
+                return Some(Outcome::Win {
+                    player: opponent(player),
+                    reason: WinReason::TooFewPieces,
+                });
+            }
+        }
+        if self.rules.full_board_draw
+            && self.in_hand == [0, 0]
+            && self.cells.iter().all(|&piece| piece != 0)
+        {
+            return Some(Outcome::Draw {
+                reason: DrawReason::FullBoard,
+            });
+        }
+        if self.repetitions() >= 3 {
+            return Some(Outcome::Draw {
+                reason: DrawReason::Repetition,
+            });
+        }
+        if let Some(limit) = self.rules.move_limit {
+            if self.quiet_moves() >= limit as usize {
+                return Some(Outcome::Draw {
+                    reason: DrawReason::MoveLimit,
+                });
+            }
+        }
+        None
+    }
+
+    pub fn quiet_moves(&self) -> usize {
+        // moves since the last placement or removal.
+        // positions before those can never come back.
+        self.moves
+            .iter()
+            .rev()
+            .take_while(|m| m.from.is_some() && m.remove.is_none())
+            .count()
+    }
+
+    pub fn repetitions(&self) -> usize {
+        // how often the current position, with the same player to move, was on the board.
+        // quiet moves only slide a piece, so taking them back on a copy of the cells is enough.
+        let mut cells = self.cells.clone();
+        let mut turn = self.turn;
+        let mut count = 1;
+        for quiet_move in self.moves.iter().rev().take(self.quiet_moves()) {
+            turn = opponent(turn);
+            cells[quiet_move.to] = 0;
+            if let Some(from) = quiet_move.from {
+                cells[from] = turn;
+            }
+            if turn == self.turn && cells == self.cells {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub fn legal_moves(&self) -> Vec<MorrisMove> {
+        let mut moves = Vec::new();
+        if self.decided().is_some() {
+            return moves;
+        }
+        let player = self.turn;
+
+        let mut steps = Vec::new();
+        if self.in_hand(player) > 0 {
+            for to in 0..self.cells.len() {
+                if self.cells[to] == 0 {
+                    steps.push(MorrisMove::place(to));
+                }
+            }
+        } else {
+            for from in 0..self.cells.len() {
+                if self.cells[from] != player {
+                    continue;
+                }
+                for to in self.destinations(from, player) {
+                    steps.push(MorrisMove::slide(from, to));
+                }
+            }
+        }
+
+        for step in steps {
+            if !self.rules.removal || !self.closes_mill(step.from, step.to, player) {
+                moves.push(step);
+                continue;
+            }
+            let targets = self.removable(player);
+            if targets.is_empty() {
+                moves.push(step);
+            }
+            for target in targets {
+                moves.push(step.removing(target));
+            }
+        }
+        moves
+    }
+
+    pub fn validate(&self, new_move: &MorrisMove) -> Result<(), GameError> {
+        if self.outcome().is_some() {
+            return Err(GameError::GameOver);
+        }
+        let player = self.turn;
+        let size = self.cells.len();
+        for point in [new_move.from, Some(new_move.to), new_move.remove].into_iter().flatten() {
+            if point >= size {
+                return Err(GameError::UnknownPoint {
+                    name: point.to_string(),
+                });
+            }
+        }
+
+        match new_move.from {
+            None => {
+                if self.in_hand(player) == 0 {
+                    return Err(GameError::MaxPiecePlayed { player });
+                }
+            }
+            Some(from) => {
+                if self.in_hand(player) > 0 {
+                    return Err(GameError::NotPlayedAllPieces { player });
+                }
+                if self.cells[from] == 0 {
+                    return Err(GameError::NoPieceOnPoint {
+                        point: self.name(from),
+                    });
+                }
+                if self.cells[from] != player {
+                    return Err(GameError::PointNotOwned {
+                        player,
+                        point: self.name(from),
+                    });
+                }
+            }
+        }
+        if self.cells[new_move.to] != 0 {
+            return Err(GameError::PointOccupied {
+                point: self.name(new_move.to),
+            });
+        }
+        if let Some(from) = new_move.from {
+            if !self.can_fly(player) && !self.board().is_adjacent(from, new_move.to) {
+                return Err(GameError::NotConnected {
+                    from: self.name(from),
+                    to: self.name(new_move.to),
+                });
+            }
+        }
+
+        let mill = self.rules.removal && self.closes_mill(new_move.from, new_move.to, player);
+        match (mill, new_move.remove) {
+            (true, None) => {
+                if !self.removable(player).is_empty() {
+                    return Err(GameError::RemovalRequired);
+                }
+            }
+            (true, Some(point)) => {
+                if !self.removable(player).contains(&point) {
+                    return Err(GameError::CannotRemove {
+                        point: self.name(point),
+                    });
+                }
+            }
+            (false, Some(_)) => return Err(GameError::RemovalNotAllowed),
+            (false, None) => {}
+        }
+        Ok(())
+    }
+
+    pub fn apply(&mut self, new_move: MorrisMove) -> Result<(), GameError> {
+        self.validate(&new_move)?;
+        self.apply_unchecked(new_move);
+        Ok(())
+    }
+
+    pub(crate) fn apply_unchecked(&mut self, new_move: MorrisMove) {
+        // apply for moves that already went through validate
+        let player = self.turn;
+        match new_move.from {
+            Some(from) => self.cells[from] = 0,
+            None => self.in_hand[player as usize - 1] -= 1,
+        }
+        self.cells[new_move.to] = player;
+        if let Some(point) = new_move.remove {
+            self.cells[point] = 0;
+        }
+        self.moves.push(new_move);
+        self.turn = opponent(player);
+    }
+
+    pub fn play(&mut self, notation: &str) -> Result<(), GameError> {
+        // parse and apply a move written in the board's notation
+        let new_move = MorrisMove::parse(self.board(), notation)?;
+        self.apply(new_move)
+    }
+
+    pub fn undo(&mut self) -> Option<MorrisMove> {
+        let last_move = self.moves.pop()?;
+        self.turn = opponent(self.turn);
+        let player = self.turn;
+        if let Some(point) = last_move.remove {
+            self.cells[point] = opponent(player);
+        }
+        self.cells[last_move.to] = 0;
+        match last_move.from {
+            Some(from) => self.cells[from] = player,
+            None => self.in_hand[player as usize - 1] += 1,
+        }
+        Some(last_move)
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        let outcome = self.outcome()?;
+        Some(GameResult {
+            outcome,
+            plies: self.moves.len(),
+        })
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(outcome) = self.decided() {
+            return Some(outcome);
+        }
+        if self.legal_moves().is_empty() {
+            return Some(Outcome::Win {
+                player: opponent(self.turn),
+                reason: WinReason::Blocked,
+            });
+        }
+        None
+    }
+}
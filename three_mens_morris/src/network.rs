@@ -0,0 +1,273 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::position::{Position, POINTS};
+use crate::types::OngoingGame;
+
+// Network runs the policy/value network of three_mens_morris_v2/simulator/model.py in plain Rust,
+// so bots in the server need no Python.
+//   input  9 points, +1 for a piece of the player to move, -1 for the opponent, 0 empty
+//   fc1    9 -> 16, ReLU
+//   fc2    16 -> 16, ReLU
+//   action_head  16 -> 9, softmax: probability of each point as the target of the next move
+//   value_head   16 -> 1, tanh: expected result for the player to move, 1 win and -1 loss
+//
+// Weights come from Model.export as JSON, keyed like the PyTorch state_dict
+// ("fc1.weight" is 16 rows of 9), or from the binary form of to_bytes:
+//   4  magic "TMNN"
+//   2  format version
+//   4  number of floats
+//   then every float as f32, little endian, in state_dict order, weights row by row
+
+const MAGIC: &[u8; 4] = b"TMNN";
+const VERSION: u16 = 1;
+const HIDDEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct Weights {
+    #[serde(rename = "fc1.weight")]
+    fc1_weight: Vec<Vec<f32>>,
+    #[serde(rename = "fc1.bias")]
+    fc1_bias: Vec<f32>,
+    #[serde(rename = "fc2.weight")]
+    fc2_weight: Vec<Vec<f32>>,
+    #[serde(rename = "fc2.bias")]
+    fc2_bias: Vec<f32>,
+    #[serde(rename = "action_head.weight")]
+    action_head_weight: Vec<Vec<f32>>,
+    #[serde(rename = "action_head.bias")]
+    action_head_bias: Vec<f32>,
+    #[serde(rename = "value_head.weight")]
+    value_head_weight: Vec<Vec<f32>>,
+    #[serde(rename = "value_head.bias")]
+    value_head_bias: Vec<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Linear {
+    inputs: usize,
+    // outputs rows of inputs weights
+    weight: Vec<f32>,
+    bias: Vec<f32>,
+}
+
+impl Linear {
+    fn from_rows(
+        name: &str,
+        rows: &[Vec<f32>],
+        bias: &[f32],
+        shape: (usize, usize),
+    ) -> Result<Linear, GameError> {
+        let (outputs, inputs) = shape;
+        if rows.len() != outputs
+            || rows.iter().any(|row| row.len() != inputs)
+            || bias.len() != outputs
+        {
+            return Err(invalid(format!(
+                "{} is not {} by {}",
+                name, outputs, inputs
+            )));
+        }
+        Ok(Linear {
+            inputs,
+            weight: rows.concat(),
+            bias: bias.to_vec(),
+        })
+    }
+
+    fn rows(&self) -> Vec<Vec<f32>> {
+        self.weight
+            .chunks(self.inputs)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weight
+            .chunks(self.inputs)
+            .zip(&self.bias)
+            .map(|(row, bias)| row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + bias)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    fc1: Linear,
+    fc2: Linear,
+    action_head: Linear,
+    value_head: Linear,
+}
+
+fn invalid(reason: String) -> GameError {
+    GameError::InvalidNetwork { reason }
+}
+
+fn relu(values: Vec<f32>) -> Vec<f32> {
+    values.into_iter().map(|v| v.max(0.0)).collect()
+}
+
+pub fn encode(game: &OngoingGame) -> [f32; POINTS] {
+    // the board seen by the player to move
+    let position = Position::from_game(game);
+    let own = position.turn as usize - 1;
+    let mut input = [0.0; POINTS];
+    for (point, value) in input.iter_mut().enumerate() {
+        if position.pieces[own] & 1 << point != 0 {
+            *value = 1.0;
+        } else if position.pieces[1 - own] & 1 << point != 0 {
+            *value = -1.0;
+        }
+    }
+    input
+}
+
+impl Network {
+    fn from_weights(weights: &Weights) -> Result<Network, GameError> {
+        Ok(Network {
+            fc1: Linear::from_rows(
+                "fc1",
+                &weights.fc1_weight,
+                &weights.fc1_bias,
+                (HIDDEN, POINTS),
+            )?,
+            fc2: Linear::from_rows(
+                "fc2",
+                &weights.fc2_weight,
+                &weights.fc2_bias,
+                (HIDDEN, HIDDEN),
+            )?,
+            action_head: Linear::from_rows(
+                "action_head",
+                &weights.action_head_weight,
+                &weights.action_head_bias,
+                (POINTS, HIDDEN),
+            )?,
+            value_head: Linear::from_rows(
+                "value_head",
+                &weights.value_head_weight,
+                &weights.value_head_bias,
+                (1, HIDDEN),
+            )?,
+        })
+    }
+
+    fn layers(&self) -> [&Linear; 4] {
+        [&self.fc1, &self.fc2, &self.action_head, &self.value_head]
+    }
+
+    pub fn from_json(json: &str) -> Result<Network, GameError> {
+        let weights: Weights = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        Network::from_weights(&weights)
+    }
+
+    pub fn to_json(&self) -> String {
+        let weights = Weights {
+            fc1_weight: self.fc1.rows(),
+            fc1_bias: self.fc1.bias.clone(),
+            fc2_weight: self.fc2.rows(),
+            fc2_bias: self.fc2.bias.clone(),
+            action_head_weight: self.action_head.rows(),
+            action_head_bias: self.action_head.bias.clone(),
+            value_head_weight: self.value_head.rows(),
+            value_head_bias: self.value_head.bias.clone(),
+        };
+        serde_json::to_string(&weights).unwrap()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let floats: Vec<f32> = self
+            .layers()
+            .iter()
+            .flat_map(|layer| layer.weight.iter().chain(&layer.bias).copied())
+            .collect();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(floats.len() as u32).to_le_bytes());
+        for float in floats {
+            bytes.extend_from_slice(&float.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Network, GameError> {
+        if bytes.len() < 10 || &bytes[..4] != MAGIC {
+            return Err(invalid(String::from("not a network file")));
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(invalid(format!("unknown format version {}", version)));
+        }
+        let count = u32::from_le_bytes(bytes[6..10].try_into().unwrap()) as usize;
+        let data = &bytes[10..];
+        if data.len() != count * 4 {
+            return Err(invalid(format!(
+                "{} bytes for {} floats",
+                data.len(),
+                count
+            )));
+        }
+        let mut floats = data
+            .chunks(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()));
+        // shapes are fixed, so the floats split the same way they were written
+        let mut take = |outputs: usize, inputs: usize| {
+            let rows: Vec<Vec<f32>> = (0..outputs)
+                .map(|_| floats.by_ref().take(inputs).collect())
+                .collect();
+            let bias: Vec<f32> = floats.by_ref().take(outputs).collect();
+            (rows, bias)
+        };
+        let (fc1_weight, fc1_bias) = take(HIDDEN, POINTS);
+        let (fc2_weight, fc2_bias) = take(HIDDEN, HIDDEN);
+        let (action_head_weight, action_head_bias) = take(POINTS, HIDDEN);
+        let (value_head_weight, value_head_bias) = take(1, HIDDEN);
+        if floats.next().is_some() {
+            return Err(invalid(format!("{} floats is too many", count)));
+        }
+        Network::from_weights(&Weights {
+            fc1_weight,
+            fc1_bias,
+            fc2_weight,
+            fc2_bias,
+            action_head_weight,
+            action_head_bias,
+            value_head_weight,
+            value_head_bias,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Network, GameError> {
+        // binary when the file starts with the magic, JSON otherwise
+        let bytes = fs::read(path).map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+        if bytes.starts_with(MAGIC) {
+            return Network::from_bytes(&bytes);
+        }
+        let json = String::from_utf8(bytes).map_err(|e| invalid(e.to_string()))?;
+        Network::from_json(&json)
+    }
+
+    pub fn forward(&self, input: &[f32; POINTS]) -> ([f32; POINTS], f32) {
+        let hidden = relu(self.fc1.forward(input));
+        let hidden = relu(self.fc2.forward(&hidden));
+        let logits = self.action_head.forward(&hidden);
+        // softmax, shifted by the largest logit so exp cannot overflow
+        let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = logits.iter().map(|logit| (logit - max).exp()).collect();
+        let total: f32 = exps.iter().sum();
+        let mut policy = [0.0; POINTS];
+        for (p, e) in policy.iter_mut().zip(&exps) {
+            *p = e / total;
+        }
+        let value = self.value_head.forward(&hidden)[0].tanh();
+        (policy, value)
+    }
+
+    pub fn predict(&self, game: &OngoingGame) -> ([f32; POINTS], f32) {
+        self.forward(&encode(game))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::bot::Bot;
+use crate::error::GameError;
+use crate::network::Network;
+use crate::types::{Move, OngoingGame};
+
+// NetworkBot plays the legal move whose target point the policy head likes best.
+// It looks one move ahead only, the network's value is not used.
+
+pub struct NetworkBot {
+    player_id: String,
+    network: Network,
+}
+
+impl NetworkBot {
+    pub fn new(id: &str, network: Network) -> NetworkBot {
+        NetworkBot {
+            player_id: String::from(id),
+            network,
+        }
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+}
+
+impl Bot for NetworkBot {
+    fn id(&self) -> &str {
+        &self.player_id
+    }
+
+    fn name(&self) -> &str {
+        "network"
+    }
+
+    fn choose_move(&self, state: &OngoingGame) -> Result<Move, GameError> {
+        if state.outcome().is_some() {
+            return Err(GameError::GameOver);
+        }
+        let (policy, _) = self.network.predict(state);
+        let mut best: Option<(f32, Move)> = None;
+        for new_move in state.legal_moves() {
+            let target = new_move.to_point_move()?.to;
+            // ties keep the first move in legal order
+            if best.as_ref().map_or(true, |(p, _)| policy[target] > *p) {
+                best = Some((policy[target], new_move));
+            }
+        }
+        best.map(|(_, new_move)| new_move)
+            .ok_or(GameError::NoLegalMove { player: state.turn })
+    }
+}
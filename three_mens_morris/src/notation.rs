@@ -0,0 +1,110 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::GameError;
+use crate::types::Move;
+
+// Notation is how a Move is written and read on the 3x3 board.
+// Goals:
+// - what Display writes, FromStr reads back to the same Move
+// - squares by name, column a-c then row 1-3: "b2" places, "a1b2" and "a1-b2" slide
+// - squares by point index 0-8 (row * 3 + col), as sent by the frontend and the simulator:
+//   "4" places, "04" and "0-4" slide
+// - errors give the character where reading failed
+// A move uses one form for both squares, "a1-4" is rejected.
+
+#[derive(Clone, Copy, PartialEq)]
+enum Form {
+    Name,
+    Index,
+}
+
+// (point, form, characters read) of the square starting at position
+fn read_square(chars: &[char], position: usize) -> Result<(usize, Form, usize), usize> {
+    match chars.get(position) {
+        Some(c) if c.is_ascii_digit() => match c.to_digit(10) {
+            Some(index) if index < 9 => Ok((index as usize, Form::Index, 1)),
+            _ => Err(position),
+        },
+        Some(c) => {
+            let col = match c.to_ascii_lowercase() {
+                'a' => 0,
+                'b' => 1,
+                'c' => 2,
+                _ => return Err(position),
+            };
+            let row = match chars.get(position + 1).and_then(|r| r.to_digit(10)) {
+                Some(row) if (1..=3).contains(&row) => row as usize - 1,
+                _ => return Err(position + 1),
+            };
+            Ok((row * 3 + col, Form::Name, 2))
+        }
+        None => Err(position),
+    }
+}
+
+fn point_to_move(point: usize) -> Move {
+    // point is below 9, so the coordinates are on the board
+    Move::coord_to_new_move(&(point / 3), &(point % 3)).unwrap()
+}
+
+impl FromStr for Move {
+    type Err = GameError;
+
+    fn from_str(input: &str) -> Result<Move, GameError> {
+        let invalid = |position: usize| GameError::InvalidNotation {
+            input: input.to_string(),
+            position,
+        };
+        let chars: Vec<char> = input.trim().chars().collect();
+        let (from, form, mut position) = read_square(&chars, 0).map_err(invalid)?;
+        if position == chars.len() {
+            return Ok(point_to_move(from));
+        }
+        if chars[position] == '-' {
+            position += 1;
+        }
+        let to_form = match chars.get(position) {
+            Some(c) if c.is_ascii_digit() => Form::Index,
+            _ => Form::Name,
+        };
+        if to_form != form {
+            return Err(invalid(position));
+        }
+        let (to, _, read) = read_square(&chars, position).map_err(invalid)?;
+        position += read;
+        if position != chars.len() {
+            return Err(invalid(position));
+        }
+        let mut new_move = point_to_move(from);
+        let destination = point_to_move(to);
+        new_move.new_col = Some(destination.col);
+        new_move.new_row = Some(destination.row);
+        Ok(new_move)
+    }
+}
+
+impl fmt::Display for Move {
+    // "b2" or "a1-b2"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.col, self.row)?;
+        if let Some(new_col) = self.new_col {
+            write!(f, "-{}", new_col)?;
+            if let Some(new_row) = self.new_row {
+                write!(f, "{}", new_row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Move {
+    pub fn index_notation(&self) -> Result<String, GameError> {
+        // the move with point indices, "4" or "0-4"
+        let point_move = self.to_point_move()?;
+        Ok(match point_move.from {
+            Some(from) => format!("{}-{}", from, point_move.to),
+            None => point_move.to.to_string(),
+        })
+    }
+}
@@ -0,0 +1,317 @@
+use std::sync::OnceLock;
+
+use crate::engine::{opponent, Outcome, WinReason};
+use crate::morris::MorrisMove;
+use crate::rules::Variant;
+use crate::types::{Move, OngoingGame};
+use crate::zobrist::{self, KEYS};
+
+// Position is the 3x3 board packed into bitmasks for search and self-play.
+// bit i of a mask is point i (row * 3 + col, like Board::three_mens).
+// Goals:
+// - move generation, apply/undo and win checks without allocating
+// - conversion from and to OngoingGame
+// Non-goals:
+// - history based rules (repetition, move limit): callers that need them keep their own history
+
+pub const POINTS: usize = 9;
+// every move a 3x3 position can have, placements or slides
+pub const MAX_MOVES: usize = 32;
+const FULL: u16 = (1 << POINTS) - 1;
+
+// adjacency and line masks of a 3x3 variant, built once from its board
+#[derive(Debug, PartialEq, Eq)]
+pub struct Masks {
+    pub adjacency: [u16; POINTS],
+    pub lines: Vec<u16>,
+}
+
+static MASKS: OnceLock<Vec<(Variant, Masks)>> = OnceLock::new();
+
+impl Masks {
+    pub fn of(variant: Variant) -> &'static Masks {
+        let all = MASKS.get_or_init(|| {
+            Variant::all()
+                .iter()
+                .filter(|variant| variant.is_grid())
+                .map(|&variant| {
+                    let rules = variant.rules();
+                    let mut adjacency = [0; POINTS];
+                    for (point, mask) in adjacency.iter_mut().enumerate() {
+                        for &neighbour in rules.board.neighbours(point) {
+                            *mask |= 1 << neighbour;
+                        }
+                    }
+                    let lines = rules
+                        .board
+                        .lines
+                        .iter()
+                        .map(|line| line.iter().fold(0, |mask, &p| mask | 1 << p))
+                        .collect();
+                    (variant, Masks { adjacency, lines })
+                })
+                .collect()
+        });
+        match all.iter().find(|(v, _)| *v == variant) {
+            Some((_, masks)) => masks,
+            // not a 3x3 variant: three men's morris masks
+            None => &all[0].1,
+        }
+    }
+}
+
+// fixed size list of moves, so generating them does not allocate
+#[derive(Clone, Copy, Debug)]
+pub struct MoveList {
+    moves: [MorrisMove; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    fn new() -> MoveList {
+        MoveList {
+            moves: [MorrisMove::place(0); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, new_move: MorrisMove) {
+        self.moves[self.len] = new_move;
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[MorrisMove] {
+        &self.moves[..self.len]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, MorrisMove> {
+        self.as_slice().iter()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    // pieces of player one and player two
+    pub pieces: [u16; 2],
+    pub in_hand: [u8; 2],
+    // player to move, 1 or 2
+    pub turn: u8,
+    pub variant: Variant,
+    masks: &'static Masks,
+    // zobrist hash, updated by apply and undo
+    hash: u64,
+}
+
+impl Position {
+    pub fn new(variant: Variant) -> Position {
+        let pieces = variant.rules().pieces;
+        let mut position = Position {
+            pieces: [0, 0],
+            in_hand: [pieces, pieces],
+            turn: 1,
+            variant,
+            masks: Masks::of(variant),
+            hash: 0,
+        };
+        position.rehash();
+        position
+    }
+
+    pub fn from_game(game: &OngoingGame) -> Position {
+        let mut pieces = [0, 0];
+        for (point, &piece) in game.cells().iter().enumerate() {
+            if piece == 1 || piece == 2 {
+                pieces[piece as usize - 1] |= 1 << point;
+            }
+        }
+        let mut position = Position {
+            pieces,
+            in_hand: [game.player_one_remaining, game.player_two_remaining],
+            turn: game.turn,
+            variant: game.variant,
+            masks: Masks::of(game.variant),
+            hash: 0,
+        };
+        position.rehash();
+        position
+    }
+
+    pub fn to_game(&self) -> OngoingGame {
+        // board, counters and turn only. players and move history are left empty.
+        let mut game = OngoingGame::new();
+        for point in 0..POINTS {
+            game.board[point / 3][point % 3] = self.piece(point);
+        }
+        game.player_one_remaining = self.in_hand[0];
+        game.player_two_remaining = self.in_hand[1];
+        game.turn = self.turn;
+        game.variant = self.variant;
+        game
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn rehash(&mut self) {
+        // recompute the hash from scratch, needed after editing the fields by hand
+        self.hash = zobrist::hash(self.pieces, self.in_hand, self.turn, self.variant);
+    }
+
+    pub fn masks(&self) -> &'static Masks {
+        self.masks
+    }
+
+    pub fn piece(&self, point: usize) -> u8 {
+        match (self.pieces[0] >> point & 1, self.pieces[1] >> point & 1) {
+            (1, _) => 1,
+            (_, 1) => 2,
+            _ => 0,
+        }
+    }
+
+    pub fn occupied(&self) -> u16 {
+        self.pieces[0] | self.pieces[1]
+    }
+
+    pub fn empty(&self) -> u16 {
+        !self.occupied() & FULL
+    }
+
+    pub fn winner(&self) -> Option<u8> {
+        // player owning every point of a line
+        for player in [1, 2] {
+            let mask = self.pieces[player as usize - 1];
+            for &line in &self.masks.lines {
+                if mask & line == line {
+                    return Some(player);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn legal_moves(&self) -> MoveList {
+        let mut moves = MoveList::new();
+        if self.winner().is_some() {
+            return moves;
+        }
+        let player = self.turn as usize - 1;
+        let empty = self.empty();
+        if self.in_hand[player] > 0 {
+            for to in bits(empty) {
+                moves.push(MorrisMove::place(to));
+            }
+            return moves;
+        }
+        for from in bits(self.pieces[player]) {
+            for to in bits(self.masks.adjacency[from] & empty) {
+                moves.push(MorrisMove::slide(from, to));
+            }
+        }
+        moves
+    }
+
+    pub fn has_legal_move(&self) -> bool {
+        let player = self.turn as usize - 1;
+        let empty = self.empty();
+        if self.in_hand[player] > 0 {
+            return empty != 0;
+        }
+        bits(self.pieces[player]).any(|from| self.masks.adjacency[from] & empty != 0)
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(player) = self.winner() {
+            return Some(Outcome::Win {
+                player,
+                reason: WinReason::ThreeInARow,
+            });
+        }
+        if !self.has_legal_move() {
+            return Some(Outcome::Win {
+                player: opponent(self.turn),
+                reason: WinReason::Blocked,
+            });
+        }
+        None
+    }
+
+    pub fn apply(&mut self, new_move: MorrisMove) {
+        // moves must come from legal_moves, nothing is checked here
+        let player = self.turn as usize - 1;
+        let keys = &KEYS.pieces[player];
+        match new_move.from {
+            Some(from) => {
+                self.pieces[player] &= !(1 << from);
+                self.hash ^= keys[from];
+            }
+            None => self.set_in_hand(player, self.in_hand[player] - 1),
+        }
+        self.pieces[player] |= 1 << new_move.to;
+        self.hash ^= keys[new_move.to] ^ KEYS.turn;
+        self.turn = opponent(self.turn);
+    }
+
+    pub fn undo(&mut self, last_move: MorrisMove) {
+        self.turn = opponent(self.turn);
+        let player = self.turn as usize - 1;
+        let keys = &KEYS.pieces[player];
+        self.pieces[player] &= !(1 << last_move.to);
+        self.hash ^= keys[last_move.to] ^ KEYS.turn;
+        match last_move.from {
+            Some(from) => {
+                self.pieces[player] |= 1 << from;
+                self.hash ^= keys[from];
+            }
+            None => self.set_in_hand(player, self.in_hand[player] + 1),
+        }
+    }
+
+    fn set_in_hand(&mut self, player: usize, count: u8) {
+        self.hash ^= zobrist::in_hand_key(player, self.in_hand[player]);
+        self.hash ^= zobrist::in_hand_key(player, count);
+        self.in_hand[player] = count;
+    }
+
+    pub fn to_move(new_move: &MorrisMove) -> Move {
+        // every point of the 3x3 board converts
+        Move::from_point_move(new_move).unwrap()
+    }
+
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        // leaf count of the move tree, to check and time move generation
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        let mut count = 0;
+        for &new_move in moves.iter() {
+            self.apply(new_move);
+            count += self.perft(depth - 1);
+            self.undo(new_move);
+        }
+        count
+    }
+}
+
+pub fn bits(mask: u16) -> impl Iterator<Item = usize> {
+    // indices of the set bits, lowest first
+    let mut rest = mask;
+    std::iter::from_fn(move || {
+        if rest == 0 {
+            return None;
+        }
+        let point = rest.trailing_zeros() as usize;
+        rest &= rest - 1;
+        Some(point)
+    })
+}
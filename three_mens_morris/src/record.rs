@@ -0,0 +1,441 @@
+use crate::engine::{DrawReason, GameResult, Outcome, WinReason};
+use crate::error::GameError;
+use crate::rules::Variant;
+use crate::types::{GameHistory, Move, OngoingGame};
+
+// GameRecord is a complete game in a PGN-like text format, to save games and replay them elsewhere.
+//
+// [PlayerOne "alice"]
+// [PlayerTwo "bob"]
+// [Date "2026.10.18"]
+// [Variant "three_mens"]
+// [MoveLimit "50"]
+// [Result "1-0"]
+// [Reason "three_in_a_row"]
+//
+// 1. b2 a2 2. a1 {threatens c3} c3 3. c1 a3 4. b2-b1 1-0
+//
+// Results are "1-0" (player one won), "0-1", "1/2-1/2" or "*" for a game still going on.
+// Reason is a WinReason or DrawReason name and is left out while the game goes on.
+// MoveLimit is the number of quiet moves before a draw or "none", the variant's when missing.
+// Moves use the notation of notation.rs. A comment belongs to the move before it,
+// a comment before the first move to the whole game.
+// Reading a record replays every move with the engine and checks the result against it.
+
+pub const UNKNOWN_DATE: &str = "????.??.??";
+// movetext lines are wrapped before this many characters
+const LINE_WIDTH: usize = 80;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedMove {
+    pub new_move: Move,
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub player_one: String,
+    pub player_two: String,
+    // YYYY.MM.DD, UNKNOWN_DATE when not known
+    pub date: String,
+    pub variant: Variant,
+    // quiet moves before a draw, None for no limit
+    pub move_limit: Option<u32>,
+    // None while the game goes on
+    pub result: Option<GameResult>,
+    pub comment: Option<String>,
+    pub moves: Vec<RecordedMove>,
+}
+
+impl GameRecord {
+    pub fn from_game(game: &OngoingGame, date: &str) -> GameRecord {
+        GameRecord {
+            player_one: game.player_one.clone(),
+            player_two: game.player_two.clone(),
+            date: date.to_string(),
+            variant: game.variant,
+            move_limit: game.move_limit,
+            result: game.result(),
+            comment: None,
+            moves: recorded(&game.moves),
+        }
+    }
+
+    pub fn from_history(history: &GameHistory, date: &str) -> GameRecord {
+        GameRecord {
+            player_one: history.player_one.clone(),
+            player_two: history.player_two.clone(),
+            date: date.to_string(),
+            variant: history.variant,
+            move_limit: history.move_limit,
+            result: history.result,
+            comment: None,
+            moves: recorded(&history.moves),
+        }
+    }
+
+    pub fn replay(&self) -> Result<OngoingGame, GameError> {
+        // the game after every move, Err at the first move the engine refuses
+        // or when the moves do not lead to the recorded result
+        let mut game = OngoingGame::with_variant(self.variant)?;
+        game.move_limit = self.move_limit;
+        game.player_one = self.player_one.clone();
+        game.player_two = self.player_two.clone();
+        game.whose_turn = game.player_one.clone();
+        for (index, recorded_move) in self.moves.iter().enumerate() {
+            game.apply(recorded_move.new_move.clone())
+                .map_err(|error| GameError::IllegalRecordMove {
+                    ply: index + 1,
+                    error: Box::new(error),
+                })?;
+        }
+
+        let mismatch = |played: Option<GameResult>| GameError::RecordResultMismatch {
+            recorded: describe(self.result),
+            played: describe(played),
+        };
+        match (game.result(), self.result) {
+            (Some(played), Some(result)) if played.outcome == result.outcome => {}
+            (Some(played), _) => return Err(mismatch(Some(played))),
+            (None, None) => {}
+            // decided off the board, the engine cannot tell
+            (None, Some(result)) => match result.outcome {
+                Outcome::Win {
+                    reason: WinReason::Resignation | WinReason::Timeout,
+                    ..
+                } => {
+                    game.ending = Some(GameResult {
+                        plies: self.moves.len(),
+                        ..result
+                    })
+                }
+                _ => return Err(mismatch(None)),
+            },
+        }
+        Ok(game)
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut output = String::new();
+        let mut header = |key: &str, value: &str| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            output.push_str(&format!("[{} \"{}\"]\n", key, value));
+        };
+        header("PlayerOne", &self.player_one);
+        header("PlayerTwo", &self.player_two);
+        header("Date", &self.date);
+        header("Variant", self.variant.name());
+        match self.move_limit {
+            Some(limit) => header("MoveLimit", &limit.to_string()),
+            None => header("MoveLimit", "none"),
+        }
+        header("Result", result_token(self.result));
+        if let Some(result) = self.result {
+            header("Reason", reason_name(result.outcome));
+        }
+        output.push('\n');
+
+        let mut tokens = Vec::new();
+        if let Some(comment) = &self.comment {
+            tokens.push(comment_token(comment));
+        }
+        for (index, recorded_move) in self.moves.iter().enumerate() {
+            if index % 2 == 0 {
+                tokens.push(format!("{}.", index / 2 + 1));
+            }
+            tokens.push(recorded_move.new_move.to_string());
+            if let Some(comment) = &recorded_move.comment {
+                tokens.push(comment_token(comment));
+            }
+        }
+        tokens.push(result_token(self.result).to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() >= LINE_WIDTH {
+                output.push_str(&line);
+                output.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        output.push_str(&line);
+        output.push('\n');
+        output
+    }
+
+    pub fn from_pgn(input: &str) -> Result<GameRecord, GameError> {
+        // read a record and check it with replay
+        let invalid = |line: usize, reason: String| GameError::InvalidRecord { line, reason };
+        let mut record = GameRecord {
+            player_one: String::new(),
+            player_two: String::new(),
+            date: UNKNOWN_DATE.to_string(),
+            variant: Variant::ThreeMens,
+            move_limit: None,
+            result: None,
+            comment: None,
+            moves: Vec::new(),
+        };
+
+        // headers, up to the first line that is not one
+        let lines: Vec<&str> = input.lines().collect();
+        let mut header_result = None;
+        let mut reason = None;
+        let mut move_limit = None;
+        let mut first_move_line = lines.len();
+        for (index, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if !line.starts_with('[') {
+                first_move_line = index;
+                break;
+            }
+            let (key, value) = read_header(line)
+                .ok_or(invalid(index + 1, format!("cannot read header {}", line)))?;
+            match key {
+                "PlayerOne" => record.player_one = value,
+                "PlayerTwo" => record.player_two = value,
+                "Date" => record.date = value,
+                "Variant" => {
+                    record.variant = Variant::from_name(&value)
+                        .ok_or(invalid(index + 1, format!("unknown variant {}", value)))?
+                }
+                "MoveLimit" => {
+                    move_limit = Some(match value.as_str() {
+                        "none" => None,
+                        _ => Some(value.parse().map_err(|_| {
+                            invalid(index + 1, format!("move limit {} is not a number", value))
+                        })?),
+                    })
+                }
+                "Result" => header_result = Some((index + 1, value)),
+                "Reason" => reason = Some((index + 1, value)),
+                // headers added by other tools
+                _ => {}
+            }
+        }
+
+        // the header may come before or after Variant
+        record.move_limit = move_limit.unwrap_or(record.variant.rules().move_limit);
+
+        // movetext
+        let mut end_result = None;
+        for (line_number, token) in movetext_tokens(&lines, first_move_line)? {
+            if end_result.is_some() {
+                return Err(invalid(line_number, format!("{} after the result", token)));
+            }
+            if let Some(comment) = token.strip_prefix('{') {
+                let comment = comment.trim_end_matches('}').trim().to_string();
+                let target = match record.moves.last_mut() {
+                    Some(last) => &mut last.comment,
+                    None => &mut record.comment,
+                };
+                *target = Some(match target.take() {
+                    Some(previous) => format!("{} {}", previous, comment),
+                    None => comment,
+                });
+                continue;
+            }
+            if RESULTS.contains(&token.as_str()) {
+                end_result = Some((line_number, token));
+                continue;
+            }
+            let mut text = token.as_str();
+            let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits > 0 && text[digits..].starts_with('.') {
+                let number: usize = text[..digits].parse().unwrap_or(0);
+                let expected = record.moves.len() / 2 + 1;
+                if number != expected {
+                    return Err(invalid(
+                        line_number,
+                        format!("move number {} where {} was expected", number, expected),
+                    ));
+                }
+                text = text[digits..].trim_start_matches('.');
+                if text.is_empty() {
+                    continue;
+                }
+            }
+            let new_move: Move = text
+                .parse()
+                .map_err(|e: GameError| invalid(line_number, e.to_string()))?;
+            record.moves.push(RecordedMove {
+                new_move,
+                comment: None,
+            });
+        }
+
+        let (line, token) = match (header_result, end_result) {
+            (Some((line, header)), Some((_, token))) if header != token => {
+                return Err(invalid(
+                    line,
+                    format!("result {} but the moves end with {}", header, token),
+                ))
+            }
+            (Some(result), _) | (None, Some(result)) => result,
+            (None, None) => (0, String::from("*")),
+        };
+        record.result = read_result(
+            &token,
+            reason.as_ref().map(|(_, r)| r.as_str()),
+            record.moves.len(),
+        )
+        .map_err(|reason_error| {
+            let line = match reason {
+                Some((reason_line, _)) => reason_line,
+                None => line,
+            };
+            invalid(line, reason_error)
+        })?;
+
+        record.replay()?;
+        Ok(record)
+    }
+}
+
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+fn recorded(moves: &[Move]) -> Vec<RecordedMove> {
+    moves
+        .iter()
+        .map(|new_move| RecordedMove {
+            new_move: new_move.clone(),
+            comment: None,
+        })
+        .collect()
+}
+
+fn comment_token(comment: &str) -> String {
+    // braces cannot be escaped inside a comment
+    format!("{{{}}}", comment.replace(['{', '}'], ""))
+}
+
+fn result_token(result: Option<GameResult>) -> &'static str {
+    match result.map(|result| result.outcome) {
+        Some(Outcome::Win { player: 1, .. }) => "1-0",
+        Some(Outcome::Win { .. }) => "0-1",
+        Some(Outcome::Draw { .. }) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+fn reason_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Win { reason, .. } => reason.name(),
+        Outcome::Draw { reason } => reason.name(),
+    }
+}
+
+fn describe(result: Option<GameResult>) -> String {
+    match result {
+        Some(result) => format!(
+            "{} {}",
+            result_token(Some(result)),
+            reason_name(result.outcome)
+        ),
+        None => String::from("*"),
+    }
+}
+
+fn read_result(
+    token: &str,
+    reason: Option<&str>,
+    plies: usize,
+) -> Result<Option<GameResult>, String> {
+    let missing = || format!("result {} needs a Reason", token);
+    let unknown = |name: &str| format!("unknown reason {} for result {}", name, token);
+    let outcome = match token {
+        "*" => return Ok(None),
+        "1-0" | "0-1" => {
+            let name = reason.ok_or_else(missing)?;
+            Outcome::Win {
+                player: if token == "1-0" { 1 } else { 2 },
+                reason: WinReason::from_name(name).ok_or_else(|| unknown(name))?,
+            }
+        }
+        _ => {
+            let name = reason.ok_or_else(missing)?;
+            Outcome::Draw {
+                reason: DrawReason::from_name(name).ok_or_else(|| unknown(name))?,
+            }
+        }
+    };
+    Ok(Some(GameResult { outcome, plies }))
+}
+
+fn read_header(line: &str) -> Option<(&str, String)> {
+    // [Key "value"] with \" and \\ escaped in the value
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (key, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut output = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => output.push(chars.next()?),
+            '"' => return None,
+            c => output.push(c),
+        }
+    }
+    Some((key, output))
+}
+
+fn movetext_tokens(lines: &[&str], first: usize) -> Result<Vec<(usize, String)>, GameError> {
+    // words and {comments} with the 1-based line they start on. comments may span lines.
+    let mut tokens = Vec::new();
+    let mut comment: Option<(usize, String)> = None;
+    for (index, line) in lines.iter().enumerate().skip(first) {
+        let mut word = String::new();
+        for c in line.chars().chain(std::iter::once('\n')) {
+            if let Some((_, text)) = comment.as_mut() {
+                text.push(if c == '\n' { ' ' } else { c });
+                if c == '}' {
+                    tokens.push(comment.take().unwrap());
+                }
+                continue;
+            }
+            if c.is_whitespace() || c == '{' {
+                if !word.is_empty() {
+                    tokens.push((index + 1, std::mem::take(&mut word)));
+                }
+                if c == '{' {
+                    comment = Some((index + 1, String::from("{")));
+                }
+                continue;
+            }
+            word.push(c);
+        }
+    }
+    match comment {
+        Some((line, _)) => Err(GameError::InvalidRecord {
+            line,
+            reason: String::from("comment is never closed"),
+        }),
+        None => Ok(tokens),
+    }
+}
+
+pub fn date(unix_seconds: u64) -> String {
+    // YYYY.MM.DD of a unix time (UTC), for the Date header
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
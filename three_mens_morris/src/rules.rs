@@ -0,0 +1,198 @@
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+
+// a player left with fewer pieces than this loses in games with removal
+pub const MIN_PIECES: u8 = 3;
+// moves without a placement or removal before built-in games are drawn
+pub const DEFAULT_MOVE_LIMIT: u32 = 50;
+
+// RuleSet is everything that differs between morris games:
+// the board topology, how many pieces each side gets and what a completed line does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleSet {
+    pub name: String,
+    pub board: Board,
+    // pieces each player places before moving
+    pub pieces: u8,
+    // true: completing a line removes an opponent piece (mill).
+    // false: completing a line wins the game.
+    pub removal: bool,
+    // a player down to MIN_PIECES may move a piece to any empty point
+    pub flying: bool,
+    // a board filled during placement ends the game in a draw
+    pub full_board_draw: bool,
+    // draw after this many moves in a row (by either player) without a placement or removal
+    pub move_limit: Option<u32>,
+}
+
+impl RuleSet {
+    pub fn three_mens_morris() -> RuleSet {
+        RuleSet {
+            name: String::from("three men's morris"),
+            board: Board::three_mens().clone(),
+            pieces: 3,
+            removal: false,
+            flying: false,
+            full_board_draw: false,
+            move_limit: Some(DEFAULT_MOVE_LIMIT),
+        }
+    }
+
+    pub fn tapatan() -> RuleSet {
+        // the Philippine game is three men's morris under another name
+        RuleSet {
+            name: String::from("tapatan"),
+            ..RuleSet::three_mens_morris()
+        }
+    }
+
+    pub fn achi() -> RuleSet {
+        // four pieces each leave a single empty point once placement is over
+        RuleSet {
+            name: String::from("achi"),
+            pieces: 4,
+            ..RuleSet::three_mens_morris()
+        }
+    }
+
+    pub fn nine_holes() -> RuleSet {
+        RuleSet {
+            name: String::from("nine holes"),
+            board: Board::nine_holes().clone(),
+            ..RuleSet::three_mens_morris()
+        }
+    }
+
+    pub fn six_mens_morris() -> RuleSet {
+        RuleSet {
+            name: String::from("six men's morris"),
+            board: Board::six_mens().clone(),
+            pieces: 6,
+            removal: true,
+            flying: false,
+            full_board_draw: false,
+            move_limit: Some(DEFAULT_MOVE_LIMIT),
+        }
+    }
+
+    pub fn nine_mens_morris() -> RuleSet {
+        RuleSet {
+            name: String::from("nine men's morris"),
+            board: Board::nine_mens().clone(),
+            pieces: 9,
+            removal: true,
+            flying: true,
+            full_board_draw: false,
+            move_limit: Some(DEFAULT_MOVE_LIMIT),
+        }
+    }
+
+    pub fn twelve_mens_morris() -> RuleSet {
+        RuleSet {
+            name: String::from("twelve men's morris"),
+            board: Board::twelve_mens().clone(),
+            pieces: 12,
+            removal: true,
+            flying: false,
+            full_board_draw: true,
+            move_limit: Some(DEFAULT_MOVE_LIMIT),
+        }
+    }
+
+    pub fn morabaraba() -> RuleSet {
+        // twelve men's morris as played in southern Africa, with flying
+        RuleSet {
+            name: String::from("morabaraba"),
+            flying: true,
+            ..RuleSet::twelve_mens_morris()
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet::three_mens_morris()
+    }
+}
+
+// Variant names a built-in RuleSet so games can store it as a plain key.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Variant {
+    #[default]
+    ThreeMens,
+    Tapatan,
+    Achi,
+    NineHoles,
+    SixMens,
+    NineMens,
+    TwelveMens,
+    Morabaraba,
+}
+
+static PRESETS: OnceLock<Vec<(Variant, Arc<RuleSet>)>> = OnceLock::new();
+
+impl Variant {
+    pub fn all() -> &'static [Variant] {
+        &[
+            Variant::ThreeMens,
+            Variant::Tapatan,
+            Variant::Achi,
+            Variant::NineHoles,
+            Variant::SixMens,
+            Variant::NineMens,
+            Variant::TwelveMens,
+            Variant::Morabaraba,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // key used in requests and records
+        match self {
+            Variant::ThreeMens => "three_mens",
+            Variant::Tapatan => "tapatan",
+            Variant::Achi => "achi",
+            Variant::NineHoles => "nine_holes",
+            Variant::SixMens => "six_mens",
+            Variant::NineMens => "nine_mens",
+            Variant::TwelveMens => "twelve_mens",
+            Variant::Morabaraba => "morabaraba",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Variant> {
+        Variant::all().iter().copied().find(|variant| variant.name() == name)
+    }
+
+    pub fn is_grid(&self) -> bool {
+        // played on the 3x3 board, so it fits in an OngoingGame
+        self.rules().board.len() == 9
+    }
+
+    pub fn rules(&self) -> Arc<RuleSet> {
+        // presets are built once and shared by every game
+        let presets = PRESETS.get_or_init(|| {
+            Variant::all()
+                .iter()
+                .map(|variant| (*variant, Arc::new(variant.build())))
+                .collect()
+        });
+        let (_, rules) = presets.iter().find(|(variant, _)| variant == self).unwrap();
+        rules.clone()
+    }
+
+    fn build(&self) -> RuleSet {
+        match self {
+            Variant::ThreeMens => RuleSet::three_mens_morris(),
+            Variant::Tapatan => RuleSet::tapatan(),
+            Variant::Achi => RuleSet::achi(),
+            Variant::NineHoles => RuleSet::nine_holes(),
+            Variant::SixMens => RuleSet::six_mens_morris(),
+            Variant::NineMens => RuleSet::nine_mens_morris(),
+            Variant::TwelveMens => RuleSet::twelve_mens_morris(),
+            Variant::Morabaraba => RuleSet::morabaraba(),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::time::{Duration, Instant};
+
+use crate::morris::MorrisMove;
+use crate::position::{bits, Position};
+use crate::types::{Move, OngoingGame};
+
+// Search is a negamax alpha-beta search over Position, for bots and for analysis.
+// Goals:
+// - iterative deepening until a depth or time limit, keeping the last finished depth
+// - move ordering: wins, then the move the table remembers, then blocks, then the rest
+// - transposition table keyed by the zobrist hash
+// - best move, score and principal variation of the last finished depth
+// Scores are for the player to move. WIN - n is a win n plies away, -(WIN - n) a loss.
+// A position coming back on the searched line scores as a draw, history before the root is not seen.
+// Such a draw depends on the line, so scores that rest on one are not kept in the table.
+
+pub const WIN: i32 = 1000;
+// scores beyond this are wins or losses found by the search, not estimates
+pub const PROVEN: i32 = WIN - 100;
+const MAX_DEPTH: u32 = 64;
+const DEFAULT_TABLE_SIZE: usize = 1 << 16;
+// nodes between two looks at the clock, a power of two
+const CLOCK_INTERVAL: u64 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub depth: u32,
+    pub time: Option<Duration>,
+}
+
+impl Limits {
+    pub fn depth(depth: u32) -> Limits {
+        Limits { depth, time: None }
+    }
+
+    pub fn time(time: Duration) -> Limits {
+        Limits {
+            depth: MAX_DEPTH,
+            time: Some(time),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    // None when the game is already over
+    pub best_move: Option<Move>,
+    pub score: i32,
+    // last depth searched to the end
+    pub depth: u32,
+    // best line for both players, starting with best_move
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    // score is at least this (the search failed high)
+    Lower,
+    // score is at most this
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    hash: u64,
+    depth: u32,
+    score: i32,
+    bound: Bound,
+    best_move: Option<MorrisMove>,
+}
+
+pub struct Search {
+    table: Vec<Option<Entry>>,
+    // hashes of the positions on the line being searched, path[ply] is the position at ply
+    path: Vec<u64>,
+    // lowest ply a repetition in the current subtree went back to, None when there was none
+    repeated: Option<usize>,
+    nodes: u64,
+    deadline: Option<Instant>,
+    stopped: bool,
+}
+
+impl Default for Search {
+    fn default() -> Search {
+        Search::new()
+    }
+}
+
+impl Search {
+    pub fn new() -> Search {
+        Search::with_table_size(DEFAULT_TABLE_SIZE)
+    }
+
+    pub fn with_table_size(entries: usize) -> Search {
+        Search {
+            table: vec![None; entries.max(1)],
+            path: Vec::new(),
+            repeated: None,
+            nodes: 0,
+            deadline: None,
+            stopped: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        // forget the table, e.g. between games
+        self.table.iter_mut().for_each(|entry| *entry = None);
+    }
+
+    pub fn search(&mut self, game: &OngoingGame, limits: Limits) -> SearchResult {
+        self.search_position(&Position::from_game(game), limits)
+    }
+
+    pub fn search_position(&mut self, position: &Position, limits: Limits) -> SearchResult {
+        self.nodes = 0;
+        self.stopped = false;
+        self.deadline = limits.time.map(|time| Instant::now() + time);
+        let mut result = SearchResult {
+            best_move: None,
+            score: evaluate(position),
+            depth: 0,
+            pv: Vec::new(),
+            nodes: 0,
+        };
+        if position.outcome().is_some() {
+            result.score = -WIN;
+            return result;
+        }
+
+        for depth in 1..=limits.depth.min(MAX_DEPTH) {
+            let mut pv = Vec::new();
+            self.path.clear();
+            self.repeated = None;
+            let score = self.negamax(*position, depth, 0, -WIN - 1, WIN + 1, &mut pv);
+            // a stopped depth is only used when no depth finished
+            if self.stopped && result.best_move.is_some() {
+                break;
+            }
+            result = SearchResult {
+                best_move: pv.first().map(Position::to_move),
+                score,
+                depth,
+                pv: pv.iter().map(Position::to_move).collect(),
+                nodes: self.nodes,
+            };
+            // nothing changes once the game is decided within the horizon
+            if self.stopped || score.abs() > PROVEN && WIN - score.abs() <= depth as i32 {
+                break;
+            }
+        }
+        if result.best_move.is_none() {
+            // stopped before the first move was searched
+            let first = ordered_moves(position, None)[0];
+            result.best_move = Some(Position::to_move(&first));
+            result.pv = vec![Position::to_move(&first)];
+        }
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn negamax(
+        &mut self,
+        mut position: Position,
+        depth: u32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<MorrisMove>,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.nodes & (CLOCK_INTERVAL - 1) == 0 {
+            if let Some(deadline) = self.deadline {
+                self.stopped |= Instant::now() >= deadline;
+            }
+        }
+        // the player to move lost: three in a row against them or no move left
+        if position.outcome().is_some() {
+            return -(WIN - ply);
+        }
+        if ply > 0 {
+            if let Some(earlier) = self.path.iter().position(|&hash| hash == position.hash()) {
+                self.repeated = self.repeated.into_iter().chain(Some(earlier)).min();
+                return 0;
+            }
+        }
+        if depth == 0 {
+            return evaluate(&position);
+        }
+
+        let hash = position.hash();
+        let slot = (hash % self.table.len() as u64) as usize;
+        let mut table_move = None;
+        if let Some(entry) = self.table[slot].filter(|entry| entry.hash == hash) {
+            table_move = entry.best_move;
+            let score = from_table(entry.score, ply);
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            // the root always searches, so there is a line to report
+            if ply > 0 && entry.depth >= depth && usable {
+                return score;
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -WIN - 1;
+        let mut best_move = None;
+        // repetitions below this node, apart from those of the caller's other moves
+        let outer_repeated = self.repeated.take();
+        self.path.push(hash);
+        for new_move in ordered_moves(&position, table_move) {
+            let mut line = Vec::new();
+            position.apply(new_move);
+            let score = -self.negamax(position, depth - 1, ply + 1, -beta, -alpha, &mut line);
+            position.undo(new_move);
+            if self.stopped {
+                break;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(new_move);
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(new_move);
+                    pv.extend(line);
+                }
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.path.pop();
+        // a repetition of this position or one below it scores the same on any line,
+        // one going back above it only on this line
+        let on_this_line = self.repeated.is_some_and(|lowest| lowest < ply as usize);
+        self.repeated = outer_repeated.into_iter().chain(self.repeated).min();
+        if self.stopped {
+            return best_score.max(alpha);
+        }
+        if on_this_line {
+            return best_score;
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table[slot] = Some(Entry {
+            hash,
+            depth,
+            score: to_table(best_score, ply),
+            bound,
+            best_move,
+        });
+        best_score
+    }
+}
+
+// the table keeps wins and losses as plies from the stored position, not from the root
+fn to_table(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > PROVEN => s + ply,
+        s if s < -PROVEN => s - ply,
+        s => s,
+    }
+}
+
+fn from_table(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > PROVEN => s - ply,
+        s if s < -PROVEN => s + ply,
+        s => s,
+    }
+}
+
+pub fn completes_line(position: &Position, player: usize, new_move: &MorrisMove) -> bool {
+    // the move leaves player with three in a row
+    let mut mask = position.pieces[player];
+    if let Some(from) = new_move.from {
+        mask &= !(1 << from);
+    }
+    mask |= 1 << new_move.to;
+    position
+        .masks()
+        .lines
+        .iter()
+        .any(|&line| line & (1 << new_move.to) != 0 && mask & line == line)
+}
+
+fn ordered_moves(position: &Position, table_move: Option<MorrisMove>) -> Vec<MorrisMove> {
+    let player = position.turn as usize - 1;
+    let opponent = 1 - player;
+    let mut moves: Vec<(i32, MorrisMove)> = position
+        .legal_moves()
+        .iter()
+        .map(|&new_move| {
+            let rank = if completes_line(position, player, &new_move) {
+                3
+            } else if Some(new_move) == table_move {
+                2
+            } else if completes_line(position, opponent, &MorrisMove::place(new_move.to)) {
+                // the opponent would finish a line on this point
+                1
+            } else {
+                0
+            };
+            (rank, new_move)
+        })
+        .collect();
+    // stable, so moves of the same rank keep the order of legal_moves
+    moves.sort_by_key(|(rank, _)| -rank);
+    moves.into_iter().map(|(_, new_move)| new_move).collect()
+}
+
+pub fn evaluate(position: &Position) -> i32 {
+    // estimate for the player to move: lines two pieces short of a win, then mobility
+    let player = position.turn as usize - 1;
+    let side = |player: usize| {
+        let own = position.pieces[player];
+        let other = position.pieces[1 - player];
+        let open_twos = position
+            .masks()
+            .lines
+            .iter()
+            .filter(|&&line| (own & line).count_ones() == 2 && other & line == 0)
+            .count() as i32;
+        let mobility: i32 = bits(own)
+            .map(|from| (position.masks().adjacency[from] & position.empty()).count_ones() as i32)
+            .sum();
+        10 * open_twos + mobility
+    };
+    side(player) - side(1 - player)
+}
@@ -0,0 +1,230 @@
+use std::io::{self, Write};
+use std::thread;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::bot::{Bot, BotFactory, Registry};
+use crate::error::GameError;
+use crate::mcts::{self, Mcts};
+use crate::position::POINTS;
+use crate::rules::Variant;
+use crate::search::{Limits, Search};
+use crate::types::{Move, OngoingGame};
+
+// SelfPlay plays many games between two configured bots on several threads
+// and keeps every position as a training sample for the network (network.rs).
+// Goals:
+// - each game gets its own seeds from the run seed and its number, so a run gives the same
+//   samples whatever the number of threads (search bots with a time limit are not repeatable,
+//   registry bots only when they choose the same move for the same position)
+// - a sample is the position, the move played, a policy target over the 9 points and
+//   the final result for the player to move
+// - the policy target is the MCTS visit share of each target point, or the point moved to
+//   for the other bots
+// - any registered bot can play, e.g. the book and network bots from Registry::with_files
+// - samples written as CSV, one row per position
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerConfig {
+    Random,
+    Search(Limits),
+    Mcts(mcts::Config),
+    // a fresh bot from the factory for every game
+    Bot(BotFactory),
+}
+
+impl PlayerConfig {
+    pub fn from_name(name: &str, registry: &Registry) -> Option<PlayerConfig> {
+        // default settings of the bots above, seeded per game, then the registry's bots
+        match name {
+            "random" => Some(PlayerConfig::Random),
+            "search" => Some(PlayerConfig::Search(Limits::depth(8))),
+            "mcts" => Some(PlayerConfig::Mcts(mcts::Config {
+                // some variety between games of the same opening
+                temperature: 1.0,
+                ..mcts::Config::default()
+            })),
+            _ => registry.factory(name).map(PlayerConfig::Bot),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub game: usize,
+    pub ply: usize,
+    // position before the move, as OngoingGame::cells
+    pub cells: [u8; POINTS],
+    pub turn: u8,
+    pub in_hand: [u8; 2],
+    pub new_move: Move,
+    pub policy: [f32; POINTS],
+    // 1 when the player to move went on to win, -1 when they lost, 0 for a draw
+    pub outcome: i8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfPlay {
+    pub games: usize,
+    pub threads: usize,
+    pub seed: u64,
+    pub variant: Variant,
+    pub players: [PlayerConfig; 2],
+    // plies after which an unfinished game counts as a draw
+    pub max_plies: usize,
+}
+
+enum Player {
+    Random(StdRng),
+    Search(Search, Limits),
+    Mcts(Mcts),
+    Bot(Box<dyn Bot>),
+}
+
+impl Player {
+    fn new(config: &PlayerConfig, seed: u64) -> Player {
+        match config {
+            PlayerConfig::Random => Player::Random(StdRng::seed_from_u64(seed)),
+            PlayerConfig::Search(limits) => Player::Search(Search::new(), *limits),
+            PlayerConfig::Mcts(config) => Player::Mcts(Mcts::with_seed(*config, seed)),
+            PlayerConfig::Bot(factory) => Player::Bot(factory.build()),
+        }
+    }
+
+    fn choose(&mut self, game: &OngoingGame) -> Option<(Move, [f32; POINTS])> {
+        // move and policy target, None when there is no move
+        let new_move = match self {
+            Player::Random(rng) => game.legal_moves().choose(rng)?.clone(),
+            Player::Search(search, limits) => search.search(game, *limits).best_move?,
+            Player::Bot(bot) => bot.choose_move(game).ok()?,
+            Player::Mcts(mcts) => {
+                let result = mcts.search(game);
+                let new_move = mcts.select_move(&result)?;
+                let total: u32 = result.moves.iter().map(|stats| stats.visits).sum();
+                let mut policy = [0.0; POINTS];
+                for stats in &result.moves {
+                    let to = stats.new_move.to_point_move().ok()?.to;
+                    policy[to] += stats.visits as f32 / total.max(1) as f32;
+                }
+                return Some((new_move, policy));
+            }
+        };
+        let mut policy = [0.0; POINTS];
+        policy[new_move.to_point_move().ok()?.to] = 1.0;
+        Some((new_move, policy))
+    }
+}
+
+fn mix(seed: u64, game: usize, player: u64) -> u64 {
+    // splitmix64 of the three, so neighbouring games get unrelated seeds
+    let mut z = seed ^ (game as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ player << 62;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl SelfPlay {
+    pub fn new(players: [PlayerConfig; 2], games: usize, seed: u64) -> SelfPlay {
+        SelfPlay {
+            games,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            seed,
+            variant: Variant::ThreeMens,
+            players,
+            max_plies: 100,
+        }
+    }
+
+    pub fn run(&self) -> Result<Vec<Sample>, GameError> {
+        // games are dealt to threads in turn and put back in order afterwards
+        let threads = self.threads.clamp(1, self.games.max(1));
+        let mut games: Vec<(usize, Vec<Sample>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|first| {
+                    scope.spawn(move || {
+                        (first..self.games)
+                            .step_by(threads)
+                            .map(|game| Ok((game, self.play(game)?)))
+                            .collect::<Result<Vec<_>, GameError>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("self-play thread panicked"))
+                .collect::<Result<Vec<_>, GameError>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect();
+        games.sort_by_key(|(game, _)| *game);
+        Ok(games.into_iter().flat_map(|(_, samples)| samples).collect())
+    }
+
+    pub fn play(&self, game_number: usize) -> Result<Vec<Sample>, GameError> {
+        // one game, the same for the same seed and game number
+        let mut players = [
+            Player::new(&self.players[0], mix(self.seed, game_number, 0)),
+            Player::new(&self.players[1], mix(self.seed, game_number, 1)),
+        ];
+        let mut game = OngoingGame::with_variant(self.variant)?;
+        let mut samples = Vec::new();
+        while game.outcome().is_none() && samples.len() < self.max_plies {
+            let turn = game.turn;
+            let Some((new_move, policy)) = players[turn as usize - 1].choose(&game) else {
+                break;
+            };
+            samples.push(Sample {
+                game: game_number,
+                ply: samples.len(),
+                cells: game.cells(),
+                turn,
+                in_hand: [game.player_one_remaining, game.player_two_remaining],
+                new_move: new_move.clone(),
+                policy,
+                outcome: 0,
+            });
+            game.apply(new_move)?;
+        }
+        if let Some(winner) = game.result().and_then(|result| result.winner()) {
+            for sample in &mut samples {
+                sample.outcome = if sample.turn == winner { 1 } else { -1 };
+            }
+        }
+        Ok(samples)
+    }
+}
+
+pub fn write_csv(samples: &[Sample], out: &mut impl Write) -> io::Result<()> {
+    // header, then one row per sample: cells c0..c8 and policy p0..p8 in point order
+    let points = |prefix: &str| {
+        (0..POINTS)
+            .map(|i| format!("{}{}", prefix, i))
+            .collect::<Vec<_>>()
+    };
+    writeln!(
+        out,
+        "game,ply,turn,one_in_hand,two_in_hand,{},move,outcome,{}",
+        points("c").join(","),
+        points("p").join(",")
+    )?;
+    for sample in samples {
+        let cells: Vec<String> = sample.cells.iter().map(|c| c.to_string()).collect();
+        let policy: Vec<String> = sample.policy.iter().map(|p| p.to_string()).collect();
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            sample.game,
+            sample.ply,
+            sample.turn,
+            sample.in_hand[0],
+            sample.in_hand[1],
+            cells.join(","),
+            sample.new_move,
+            sample.outcome,
+            policy.join(",")
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::morris::MorrisMove;
+use crate::position::Position;
+use crate::rules::Variant;
+use crate::types::OngoingGame;
+
+// Solver labels every reachable position of a 3x3 variant with its value under perfect play,
+// found by retrograde analysis: start from the finished positions and work back to the start.
+// Goals:
+// - value of every position of the placement and movement phases, for the player to move
+// - distance in plies to the end of the game: shortest for a win, longest for a loss
+// - ground truth for bots and hints (best_moves)
+// Non-goals:
+// - history based draws (repetition, move limit): a draw means neither side can force a win
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    // for the player to move
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Value {
+    pub outcome: Outcome,
+    // plies until the game ends, 0 for draws
+    pub distance: u8,
+}
+
+pub struct Solution {
+    pub variant: Variant,
+    positions: Vec<Position>,
+    values: Vec<Value>,
+    index: HashMap<u32, usize>,
+}
+
+pub fn key(position: &Position) -> u32 {
+    // exact key of a position of a single variant: both piece masks, pieces in hand, player to move
+    position.pieces[0] as u32
+        | (position.pieces[1] as u32) << 9
+        | (position.in_hand[0] as u32) << 18
+        | (position.in_hand[1] as u32) << 22
+        | ((position.turn == 2) as u32) << 26
+}
+
+impl Solution {
+    pub fn solve(variant: Variant) -> Result<Solution, GameError> {
+        if !variant.is_grid() {
+            return Err(GameError::UnsupportedVariant {
+                name: variant.name().to_string(),
+            });
+        }
+
+        // every position a game can reach, with the moves between them
+        let start = Position::new(variant);
+        let mut positions = vec![start];
+        let mut index = HashMap::from([(key(&start), 0)]);
+        let mut children: Vec<Vec<usize>> = Vec::new();
+        let mut next = 0;
+        while next < positions.len() {
+            let position = positions[next];
+            let mut list = Vec::new();
+            // finished positions have no moves
+            if position.outcome().is_none() {
+                for &new_move in position.legal_moves().iter() {
+                    let mut child = position;
+                    child.apply(new_move);
+                    let child_index = *index.entry(key(&child)).or_insert_with(|| {
+                        positions.push(child);
+                        positions.len() - 1
+                    });
+                    list.push(child_index);
+                }
+            }
+            children.push(list);
+            next += 1;
+        }
+        let mut parents = vec![Vec::new(); positions.len()];
+        for (parent, list) in children.iter().enumerate() {
+            for &child in list {
+                parents[child].push(parent);
+            }
+        }
+
+        // finished positions are lost for the player to move: the opponent made three in a row
+        // or left them without a move. work back from there in order of distance.
+        let mut values: Vec<Option<Value>> = vec![None; positions.len()];
+        let mut unresolved: Vec<usize> = children.iter().map(|list| list.len()).collect();
+        let mut queue = VecDeque::new();
+        for (i, position) in positions.iter().enumerate() {
+            if position.outcome().is_some() {
+                values[i] = Some(Value {
+                    outcome: Outcome::Loss,
+                    distance: 0,
+                });
+                queue.push_back(i);
+            }
+        }
+        while let Some(i) = queue.pop_front() {
+            let value = values[i].unwrap();
+            let distance = value.distance.saturating_add(1);
+            for &parent in &parents[i] {
+                if values[parent].is_some() {
+                    continue;
+                }
+                match value.outcome {
+                    // a move into a lost position wins, the first one found is the quickest
+                    Outcome::Loss => {
+                        values[parent] = Some(Value {
+                            outcome: Outcome::Win,
+                            distance,
+                        });
+                        queue.push_back(parent);
+                    }
+                    // lost once every move wins for the opponent, the last one found holds out longest
+                    _ => {
+                        unresolved[parent] -= 1;
+                        if unresolved[parent] == 0 {
+                            values[parent] = Some(Value {
+                                outcome: Outcome::Loss,
+                                distance,
+                            });
+                            queue.push_back(parent);
+                        }
+                    }
+                }
+            }
+        }
+
+        let draw = Value {
+            outcome: Outcome::Draw,
+            distance: 0,
+        };
+        Ok(Solution {
+            variant,
+            values: values
+                .into_iter()
+                .map(|value| value.unwrap_or(draw))
+                .collect(),
+            positions,
+            index,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Position, &Value)> {
+        self.positions.iter().zip(self.values.iter())
+    }
+
+    pub fn value(&self, position: &Position) -> Option<Value> {
+        // None for positions of another variant or that no game reaches
+        if position.variant != self.variant {
+            return None;
+        }
+        self.index.get(&key(position)).map(|&i| self.values[i])
+    }
+
+    pub fn probe(&self, game: &OngoingGame) -> Option<Value> {
+        self.value(&Position::from_game(game))
+    }
+
+    pub fn best_moves(&self, position: &Position) -> Vec<MorrisMove> {
+        // moves keeping the value of the position: the quickest win,
+        // any move holding the draw, or the longest resistance
+        let Some(value) = self.value(position) else {
+            return Vec::new();
+        };
+        position
+            .legal_moves()
+            .iter()
+            .copied()
+            .filter(|&new_move| {
+                let mut child = *position;
+                child.apply(new_move);
+                let Some(child_value) = self.value(&child) else {
+                    return false;
+                };
+                let distance = child_value.distance.saturating_add(1);
+                match value.outcome {
+                    Outcome::Win => {
+                        child_value.outcome == Outcome::Loss && distance == value.distance
+                    }
+                    Outcome::Draw => child_value.outcome == Outcome::Draw,
+                    Outcome::Loss => distance == value.distance,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::morris::MorrisMove;
+use crate::position::{bits, Position, POINTS};
+use crate::types::Move;
+
+// The 3x3 board looks the same after any of eight rotations and reflections,
+// so positions related by one of them have the same value and equivalent moves.
+// canonical picks one representative per class and tells which transform led there.
+// a move found in the canonical position is played in the original through transform.inverse().
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    // clockwise
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    // mirror left to right (a <-> c)
+    FlipHorizontal,
+    // mirror top to bottom (1 <-> 3)
+    FlipVertical,
+    // across a1-c3
+    FlipDiagonal,
+    // across c1-a3
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub fn all() -> [Transform; 8] {
+        [
+            Transform::Identity,
+            Transform::Rotate90,
+            Transform::Rotate180,
+            Transform::Rotate270,
+            Transform::FlipHorizontal,
+            Transform::FlipVertical,
+            Transform::FlipDiagonal,
+            Transform::FlipAntiDiagonal,
+        ]
+    }
+
+    pub fn inverse(self) -> Transform {
+        match self {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => other,
+        }
+    }
+
+    pub fn point(self, point: usize) -> usize {
+        // where point (row * 3 + col) ends up
+        let (row, col) = (point / 3, point % 3);
+        let (row, col) = match self {
+            Transform::Identity => (row, col),
+            Transform::Rotate90 => (col, 2 - row),
+            Transform::Rotate180 => (2 - row, 2 - col),
+            Transform::Rotate270 => (2 - col, row),
+            Transform::FlipHorizontal => (row, 2 - col),
+            Transform::FlipVertical => (2 - row, col),
+            Transform::FlipDiagonal => (col, row),
+            Transform::FlipAntiDiagonal => (2 - col, 2 - row),
+        };
+        row * 3 + col
+    }
+
+    pub fn mask(self, mask: u16) -> u16 {
+        bits(mask).fold(0, |out, point| out | 1 << self.point(point))
+    }
+
+    pub fn cells(self, cells: &[u8; POINTS]) -> [u8; POINTS] {
+        let mut out = [0; POINTS];
+        for (point, &piece) in cells.iter().enumerate() {
+            out[self.point(point)] = piece;
+        }
+        out
+    }
+
+    pub fn position(self, position: &Position) -> Position {
+        let mut out = *position;
+        out.pieces = [self.mask(position.pieces[0]), self.mask(position.pieces[1])];
+        out.rehash();
+        out
+    }
+
+    pub fn point_move(self, new_move: &MorrisMove) -> MorrisMove {
+        MorrisMove {
+            from: new_move.from.map(|from| self.point(from)),
+            to: self.point(new_move.to),
+            remove: new_move.remove.map(|remove| self.point(remove)),
+        }
+    }
+
+    pub fn game_move(self, new_move: &Move) -> Result<Move, GameError> {
+        // same as point_move for moves of an OngoingGame
+        Move::from_point_move(&self.point_move(&new_move.to_point_move()?))
+    }
+}
+
+impl Position {
+    pub fn canonical(&self) -> (Position, Transform) {
+        // the transformed position with the smallest piece masks, and the transform giving it.
+        // ties keep the first transform of Transform::all, so Identity wins when symmetric.
+        let key = |pieces: [u16; 2]| (pieces[0] as u32) << 16 | pieces[1] as u32;
+        let mut best = Transform::Identity;
+        let mut best_key = key(self.pieces);
+        for transform in Transform::all() {
+            let pieces = [transform.mask(self.pieces[0]), transform.mask(self.pieces[1])];
+            if key(pieces) < best_key {
+                best = transform;
+                best_key = key(pieces);
+            }
+        }
+        (best.position(self), best)
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::GameError;
+use crate::position::{Masks, Position, POINTS};
+use crate::rules::Variant;
+use crate::solver::{Outcome, Solution};
+use crate::symmetry::Transform;
+use crate::types::{Move, OngoingGame};
+
+// Tablebase is a Solution saved to a binary file, so bots and the server look up
+// perfect play without solving again.
+//
+// All numbers are little endian.
+//   4  magic "TMTB"
+//   2  format version
+//   1  length of the variant name, then the name (Variant::name)
+//   1  pieces per player
+//   8  fingerprint of the rules: pieces, adjacency and lines of the board
+//   4  number of entries
+//   8  FNV-1a checksum of the entries
+//   then 2 bytes per entry: outcome (0 unknown, 1 win, 2 loss, 3 draw) and distance
+//
+// Entry index is canonical_index: the rank of the board among boards that are their own
+// canonical form (symmetry.rs), times 2, plus 1 when player two is to move.
+// Pieces in hand follow from the board in 3x3 variants, so they are not part of the index.
+
+const MAGIC: &[u8; 4] = b"TMTB";
+const VERSION: u16 = 1;
+const ENTRY_SIZE: usize = 2;
+
+static RANKS: OnceLock<HashMap<[u16; 2], usize>> = OnceLock::new();
+
+fn ranks() -> &'static HashMap<[u16; 2], usize> {
+    // canonical boards numbered in order of their cells read as a base 3 number
+    RANKS.get_or_init(|| {
+        let mut ranks = HashMap::new();
+        for cells in 0..3usize.pow(POINTS as u32) {
+            let mut pieces = [0u16; 2];
+            let mut rest = cells;
+            for point in 0..POINTS {
+                if rest % 3 > 0 {
+                    pieces[rest % 3 - 1] |= 1 << point;
+                }
+                rest /= 3;
+            }
+            let canonical = Transform::all()
+                .iter()
+                .map(|transform| [transform.mask(pieces[0]), transform.mask(pieces[1])])
+                .min_by_key(|masks| (masks[0], masks[1]))
+                .unwrap();
+            if canonical == pieces {
+                let rank = ranks.len();
+                ranks.insert(pieces, rank);
+            }
+        }
+        ranks
+    })
+}
+
+pub fn entries() -> usize {
+    ranks().len() * 2
+}
+
+pub fn canonical_index(position: &Position) -> Option<usize> {
+    // None when the position cannot be in a table: a shared point or no player to move
+    if position.pieces[0] & position.pieces[1] != 0 || !(1..=2).contains(&position.turn) {
+        return None;
+    }
+    let (canonical, _) = position.canonical();
+    let rank = ranks().get(&canonical.pieces)?;
+    Some(rank * 2 + position.turn as usize - 1)
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub fn fingerprint(variant: Variant) -> u64 {
+    // changes whenever the rules of the variant change, so old files are refused
+    let masks = Masks::of(variant);
+    let mut bytes = vec![variant.rules().pieces];
+    for mask in masks.adjacency.iter().chain(masks.lines.iter()) {
+        bytes.extend_from_slice(&mask.to_le_bytes());
+    }
+    fnv1a(&bytes)
+}
+
+enum Storage {
+    Memory(Vec<u8>),
+    // entries are read from the file when probed
+    File { file: Mutex<File>, offset: u64 },
+}
+
+pub struct Tablebase {
+    pub variant: Variant,
+    storage: Storage,
+}
+
+impl Tablebase {
+    pub fn from_solution(solution: &Solution) -> Tablebase {
+        let mut data = vec![0; entries() * ENTRY_SIZE];
+        for (position, value) in solution.iter() {
+            // every reachable position has a canonical index
+            let index = canonical_index(position).unwrap();
+            data[index * ENTRY_SIZE] = match value.outcome {
+                Outcome::Win => 1,
+                Outcome::Loss => 2,
+                Outcome::Draw => 3,
+            };
+            data[index * ENTRY_SIZE + 1] = value.distance;
+        }
+        Tablebase {
+            variant: solution.variant,
+            storage: Storage::Memory(data),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, GameError> {
+        let data = self.data()?;
+        let name = self.variant.name().as_bytes();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name);
+        bytes.push(self.variant.rules().pieces);
+        bytes.extend_from_slice(&fingerprint(self.variant).to_le_bytes());
+        bytes.extend_from_slice(&(entries() as u32).to_le_bytes());
+        bytes.extend_from_slice(&fnv1a(&data).to_le_bytes());
+        bytes.extend_from_slice(&data);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Tablebase, GameError> {
+        // header and checksum are checked before anything is probed
+        let (variant, offset) = read_header(&mut &bytes[..])?;
+        let data = &bytes[offset as usize..];
+        if data.len() != entries() * ENTRY_SIZE {
+            return Err(invalid(format!(
+                "{} bytes of entries instead of {}",
+                data.len(),
+                entries() * ENTRY_SIZE
+            )));
+        }
+        let tablebase = Tablebase {
+            variant,
+            storage: Storage::Memory(data.to_vec()),
+        };
+        tablebase.verify_against(&bytes[offset as usize - 8..offset as usize])?;
+        Ok(tablebase)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), GameError> {
+        fs::write(path, self.to_bytes()?).map_err(|e| invalid(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Tablebase, GameError> {
+        // the whole file in memory
+        let bytes = fs::read(path).map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+        Tablebase::from_bytes(&bytes)
+    }
+
+    pub fn open(path: &Path) -> Result<Tablebase, GameError> {
+        // entries are read as they are probed and not kept in memory.
+        // the file is read through once here to check the checksum, like load does.
+        let io_error = |e: std::io::Error| invalid(format!("{}: {}", path.display(), e));
+        let mut file = File::open(path).map_err(io_error)?;
+        let (variant, offset) = read_header(&mut file)?;
+        let size = file.metadata().map_err(io_error)?.len();
+        if size != offset + (entries() * ENTRY_SIZE) as u64 {
+            return Err(invalid(format!("{} is cut short", path.display())));
+        }
+        let tablebase = Tablebase {
+            variant,
+            storage: Storage::File {
+                file: Mutex::new(file),
+                offset,
+            },
+        };
+        tablebase.verify()?;
+        Ok(tablebase)
+    }
+
+    pub fn verify(&self) -> Result<(), GameError> {
+        // checksum of the entries against the header, again for a file that may have
+        // changed since open
+        let checksum = match &self.storage {
+            Storage::Memory(_) => return Ok(()),
+            Storage::File { file, offset } => {
+                let mut file = file.lock().unwrap();
+                let mut checksum = [0; 8];
+                file.seek(SeekFrom::Start(offset - 8))
+                    .and_then(|_| file.read_exact(&mut checksum))
+                    .map_err(|e| invalid(e.to_string()))?;
+                checksum
+            }
+        };
+        self.verify_against(&checksum)
+    }
+
+    fn verify_against(&self, checksum: &[u8]) -> Result<(), GameError> {
+        if fnv1a(&self.data()?).to_le_bytes() != checksum {
+            return Err(invalid(String::from("checksum does not match the entries")));
+        }
+        Ok(())
+    }
+
+    fn data(&self) -> Result<Vec<u8>, GameError> {
+        match &self.storage {
+            Storage::Memory(data) => Ok(data.clone()),
+            Storage::File { file, offset } => {
+                let mut file = file.lock().unwrap();
+                let mut data = vec![0; entries() * ENTRY_SIZE];
+                file.seek(SeekFrom::Start(*offset))
+                    .and_then(|_| file.read_exact(&mut data))
+                    .map_err(|e| invalid(e.to_string()))?;
+                Ok(data)
+            }
+        }
+    }
+
+    fn entry(&self, index: usize) -> Option<[u8; ENTRY_SIZE]> {
+        match &self.storage {
+            Storage::Memory(data) => {
+                let start = index * ENTRY_SIZE;
+                Some([data[start], data[start + 1]])
+            }
+            Storage::File { file, offset } => {
+                let mut entry = [0; ENTRY_SIZE];
+                let mut file = file.lock().ok()?;
+                file.seek(SeekFrom::Start(offset + (index * ENTRY_SIZE) as u64))
+                    .ok()?;
+                file.read_exact(&mut entry).ok()?;
+                Some(entry)
+            }
+        }
+    }
+
+    pub fn probe_position(&self, position: &Position) -> Option<(Outcome, u8)> {
+        // value for the player to move, None for other variants and positions no game reaches
+        if position.variant != self.variant {
+            return None;
+        }
+        // pieces in hand are not in the index, so they have to match the board
+        let pieces = self.variant.rules().pieces;
+        for player in 0..2 {
+            if position.pieces[player].count_ones() as u16 + position.in_hand[player] as u16
+                != pieces as u16
+            {
+                return None;
+            }
+        }
+        let [outcome, distance] = self.entry(canonical_index(position)?)?;
+        let outcome = match outcome {
+            1 => Outcome::Win,
+            2 => Outcome::Loss,
+            3 => Outcome::Draw,
+            _ => return None,
+        };
+        Some((outcome, distance))
+    }
+
+    pub fn probe(&self, game: &OngoingGame) -> Option<(Outcome, u8)> {
+        self.probe_position(&Position::from_game(game))
+    }
+
+    pub fn best_moves(&self, game: &OngoingGame) -> Vec<Move> {
+        // perfect moves by probing every move, same choice as Solution::best_moves
+        let position = Position::from_game(game);
+        let Some((outcome, distance)) = self.probe_position(&position) else {
+            return Vec::new();
+        };
+        position
+            .legal_moves()
+            .iter()
+            .filter(|&&new_move| {
+                let mut child = position;
+                child.apply(new_move);
+                match (outcome, self.probe_position(&child)) {
+                    (Outcome::Win, Some((Outcome::Loss, after))) => after.saturating_add(1) == distance,
+                    (Outcome::Draw, Some((Outcome::Draw, _))) => true,
+                    (Outcome::Loss, Some((_, after))) => after.saturating_add(1) == distance,
+                    _ => false,
+                }
+            })
+            .map(Position::to_move)
+            .collect()
+    }
+}
+
+fn invalid(reason: String) -> GameError {
+    GameError::InvalidTablebase { reason }
+}
+
+fn read_header(input: &mut impl Read) -> Result<(Variant, u64), GameError> {
+    // variant and the offset of the first entry
+    let mut read = |length: usize| -> Result<Vec<u8>, GameError> {
+        let mut buffer = vec![0; length];
+        input
+            .read_exact(&mut buffer)
+            .map_err(|_| invalid(String::from("header is cut short")))?;
+        Ok(buffer)
+    };
+    if read(4)? != MAGIC {
+        return Err(invalid(String::from("not a tablebase file")));
+    }
+    let version = u16::from_le_bytes(read(2)?.try_into().unwrap());
+    if version != VERSION {
+        return Err(invalid(format!("unknown format version {}", version)));
+    }
+    let name_length = read(1)?[0] as usize;
+    let name = String::from_utf8_lossy(&read(name_length)?).to_string();
+    let variant = Variant::from_name(&name).ok_or(GameError::UnknownVariant { name })?;
+    let pieces = read(1)?[0];
+    let fingerprint_bytes: [u8; 8] = read(8)?.try_into().unwrap();
+    if pieces != variant.rules().pieces
+        || u64::from_le_bytes(fingerprint_bytes) != fingerprint(variant)
+    {
+        return Err(invalid(format!(
+            "made for other {} rules, solve again",
+            variant.name()
+        )));
+    }
+    let count = u32::from_le_bytes(read(4)?.try_into().unwrap()) as usize;
+    if count != entries() {
+        return Err(invalid(format!(
+            "{} entries instead of {}",
+            count,
+            entries()
+        )));
+    }
+    // checksum, checked once the entries are read
+    read(8)?;
+    Ok((variant, (4 + 2 + 1 + name_length + 1 + 8 + 4 + 8) as u64))
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::engine::{GameResult, MorrisCache};
+use crate::error::GameError;
+use crate::rules::{Variant, DEFAULT_MOVE_LIMIT};
+
 // database collection games
 // has a key of game and stores uuid as string
 // has a column for player 1 and stores uuid as string
@@ -8,7 +12,7 @@ use serde::{Deserialize, Serialize};
 // has a column named "winner" and store uuid as string
 // has a column named "moves" and store string moves delimited by space
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Move {
     // Move is a human-readible symantic move record
     pub col: char,
@@ -17,12 +21,12 @@ pub struct Move {
     pub new_row: Option<u8>,
 }
 
-fn char_to_index(c: char) -> Result<usize, ()> {
-    match c {
-        'a' | '1' => Ok(0),
-        'b' | '2' => Ok(1),
-        'c' | '3' => Ok(2),
-        _ => Err(()),
+fn index_to_col(index: usize) -> Option<char> {
+    match index {
+        0 => Some('a'),
+        1 => Some('b'),
+        2 => Some('c'),
+        _ => None,
     }
 }
 
@@ -36,43 +40,20 @@ impl Move {
         }
     }
 
-    pub fn string_to_move(str_move: &String) -> Result<Self, ()> {
-        // parse string and convert first character a b c to 0 1 2. then convert second charcter 1 2 3 to 0 1 2 and return Move
-        if str_move.len() != 2 && str_move.len() != 4 {
-            println!("Incorrect length input move to convert.");
-            return Err(());
-        }
-    
-        let chars: Vec<char> = str_move.chars().collect();
-        println!("string_to_move char {:?}", chars);
-        if chars.len() == 4 {
-            let from_col = char_to_index(chars[0])?;
-            let from_row = char_to_index(chars[1])?;
-            let to_col = char_to_index(chars[2])?;
-            let to_row = char_to_index(chars[3])?;
-            return Move::coord_to_move(&from_row, &from_col, &to_row, &to_col);
-        } else if chars.len() == 2 {
-            let col = char_to_index(chars[0])?;
-            let row = char_to_index(chars[1])?;
-            return Move::coord_to_new_move(&row, &col);
-        } else {
-            return Err(())
-        }
+    pub fn string_to_move(str_move: &String) -> Result<Self, GameError> {
+        // "a1", "a1b2", "a1-b2" or point indices, see notation
+        str_move.parse()
     }
 
-    pub fn coord_to_new_move(row: &usize, col: &usize) -> Result<Self, ()> {
+    pub fn coord_to_new_move(row: &usize, col: &usize) -> Result<Self, GameError> {
         // Take coordinates and returns Move
         // acceptable rows and cols = 0 to 2
-        if *row > 2 || *col > 2 {
-            return Err(());
+        let out_of_board = GameError::OutOfBoard { row: *row, col: *col };
+        if *row > 2 {
+            return Err(out_of_board);
         }
+        let col = index_to_col(*col).ok_or(out_of_board)?;
         let row = *row as u8 + 1;
-        let col = match col {
-            0 => 'a',
-            1 => 'b',
-            2 => 'c',
-            _ => return Err(()),
-        };
 
         Ok(
             Self {
@@ -83,95 +64,201 @@ impl Move {
         })
     }
 
-    pub fn coord_to_move(old_row: &usize, old_col: &usize, new_row: &usize, new_col: &usize) -> Result<Self, ()> {
+    pub fn coord_to_move(old_row: &usize, old_col: &usize, new_row: &usize, new_col: &usize) -> Result<Self, GameError> {
         // acceptable rows and cols = 0 to 2
-        if *old_row > 2 || *old_col > 2 {
-            return Err(());
-        }
-
-        let old_col = match old_col {
-            0 => 'a',
-            1 => 'b',
-            2 => 'c',
-            _ => return Err(()),
-        };
-        let old_row = *old_row as u8 + 1;
-
-        let new_col = match new_col {
-            0 => 'a',
-            1 => 'b',
-            2 => 'c',
-            _ => ' ',
-        };
-        let new_row = *new_row as u8 + 1;
-
-        Ok(Self {
-            col: old_col,
-            row: old_row,
-            new_col: Some(new_col),
-            new_row: Some(new_row),
-        })
+        let mut new_move = Move::coord_to_new_move(old_row, old_col)?;
+        let destination = Move::coord_to_new_move(new_row, new_col)?;
+        new_move.new_col = Some(destination.col);
+        new_move.new_row = Some(destination.row);
+        Ok(new_move)
     }
 
-    pub fn as_coord(&self) -> Result<(usize, usize, Option<usize>, Option<usize>), ()> {
+    pub fn as_coord(&self) -> Result<(usize, usize, Option<usize>, Option<usize>), GameError> {
+        // positions in errors point into print(), e.g. "a1-b2"
+        let invalid = |position: usize| GameError::InvalidNotation {
+            input: self.print(),
+            position,
+        };
         let old_col: usize = match self.col {
             'A' | 'a' => 0,
             'B' | 'b' => 1,
             'C' | 'c' => 2,
-            _ => return Err(()),
+            _ => return Err(invalid(0)),
         };
+        if !(1..=3).contains(&self.row) {
+            return Err(invalid(1));
+        }
         // if new col is None then move is new move
         if self.new_col.is_none() {
-            return Ok((self.row as usize - 1, old_col.clone(), None, None));
+            return Ok((self.row as usize - 1, old_col, None, None));
         };
-        let new_col: usize = match self.col {
-            'A' | 'a' => 0,
-            'B' | 'b' => 1,
-            'C' | 'c' => 2,
-            _ => return Err(()),
+        let new_col: usize = match self.new_col {
+            Some('A') | Some('a') => 0,
+            Some('B') | Some('b') => 1,
+            Some('C') | Some('c') => 2,
+            _ => return Err(invalid(3)),
         };
         let new_row: usize = match self.new_row {
-            Some(row) => row as usize - 1,
-            None => return Err(()),
+            Some(row) if (1..=3).contains(&row) => row as usize - 1,
+            _ => return Err(invalid(4)),
         };
-        return Ok((self.row as usize - 1, old_col.clone(), Some(new_row), Some(new_col)));
+        Ok((self.row as usize - 1, old_col, Some(new_row), Some(new_col)))
     }
 
     pub fn print(&self) -> String {
-        let mut output = String::new();
-        // print order e.g. a1
-        output.push(self.col);
-        output.push_str(&self.row.to_string());
-        
-        if self.new_col.is_some() {
-            output.push_str(&String::from("->"));
-            output.push(self.new_col.unwrap());
-            output.push_str(&self.new_row.unwrap().to_string());
-        }
-        output
+        // e.g. a1 or a1-b2, readable by string_to_move
+        self.to_string()
     }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(try_from = "StoredHistory")]
 pub struct GameHistory {
     pub _id: String,
     pub player_one: String,
     pub player_two: String,
+    // player id, empty when the game is a draw
     pub winner: String,
-    pub moves: Vec<(char, char)>,
+    // None for histories stored before results were kept that the moves do not decide
+    pub result: Option<GameResult>,
+    pub moves: Vec<Move>,
+    // three men's morris for histories stored before variants
+    #[serde(default)]
+    pub variant: Variant,
+    // None for no limit, 50 for histories stored before the limit was kept
+    #[serde(default = "default_move_limit")]
+    pub move_limit: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// GameHistory as any version stored it, see TryFrom below
+#[derive(Deserialize)]
+struct StoredHistory {
+    _id: String,
+    player_one: String,
+    player_two: String,
+    winner: String,
+    #[serde(default)]
+    result: Option<GameResult>,
+    moves: StoredMoves,
+    #[serde(default)]
+    variant: Variant,
+    #[serde(default = "default_move_limit")]
+    move_limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredMoves {
+    Moves(Vec<Move>),
+    // first versions: (column, row) of every point played, e.g. ('a', '1')
+    Points(Vec<(char, char)>),
+}
+
+impl TryFrom<StoredHistory> for GameHistory {
+    type Error = GameError;
+
+    fn try_from(stored: StoredHistory) -> Result<GameHistory, GameError> {
+        let moves = match stored.moves {
+            StoredMoves::Moves(moves) => moves,
+            StoredMoves::Points(points) => points_to_moves(&points)?,
+        };
+        // older histories have no result, the engine recovers it from the moves if it can
+        let result = stored.result.or_else(|| {
+            let mut game = OngoingGame::with_variant(stored.variant).ok()?;
+            game.move_limit = stored.move_limit;
+            for new_move in &moves {
+                game.apply(new_move.clone()).ok()?;
+            }
+            game.result()
+        });
+        Ok(GameHistory {
+            _id: stored._id,
+            player_one: stored.player_one,
+            player_two: stored.player_two,
+            winner: stored.winner,
+            result,
+            moves,
+            variant: stored.variant,
+            move_limit: stored.move_limit,
+        })
+    }
+}
+
+fn points_to_moves(points: &[(char, char)]) -> Result<Vec<Move>, GameError> {
+    // those games were three men's morris: six placements, then a pair of points per move
+    let (placements, slides) = points.split_at(points.len().min(6));
+    if slides.len() % 2 != 0 {
+        return Err(GameError::InvalidNotation {
+            input: format!("{:?}", points),
+            position: points.len() - 1,
+        });
+    }
+    let placements = placements
+        .iter()
+        .map(|(col, row)| format!("{}{}", col, row).parse());
+    let slides = slides
+        .chunks(2)
+        .map(|pair| format!("{}{}-{}{}", pair[0].0, pair[0].1, pair[1].0, pair[1].1).parse());
+    placements.chain(slides).collect()
+}
+
+impl GameHistory {
+    pub fn from_game(game: &OngoingGame) -> Option<GameHistory> {
+        // record of a finished game, None while it goes on
+        let result = game.result()?;
+        let winner = match result.winner() {
+            Some(1) => game.player_one.clone(),
+            Some(_) => game.player_two.clone(),
+            None => String::new(),
+        };
+        Some(GameHistory {
+            _id: game._id.clone(),
+            player_one: game.player_one.clone(),
+            player_two: game.player_two.clone(),
+            winner,
+            result: Some(result),
+            moves: game.moves.clone(),
+            variant: game.variant,
+            move_limit: game.move_limit,
+        })
+    }
+}
+
+fn default_turn() -> u8 {
+    1
+}
+
+fn default_move_limit() -> Option<u32> {
+    Some(DEFAULT_MOVE_LIMIT)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct OngoingGame {
     pub _id: String,
     pub player_one: String,
     pub player_two: String,
     pub whose_turn: String,
+    // piece of the player to move, 1 or 2. whose_turn is kept in sync by update_turn
+    #[serde(default = "default_turn")]
+    pub turn: u8,
     // player one is 1 and player two is 2 for peice
     pub player_one_remaining: u8,
     pub player_two_remaining: u8,
     pub board: [[u8; 3]; 3], // (row x col x 3) matrix where 0 is empty, 1 is 1st player, and 2 is 2nd player
     pub moves: Vec<Move>,
+    // rules played on the 3x3 board, three men's morris for games stored before variants
+    #[serde(default)]
+    pub variant: Variant,
+    // set when the game ended off the board, by resignation or timeout
+    #[serde(default)]
+    pub ending: Option<GameResult>,
+    // quiet moves before a draw (RuleSet::move_limit), None for no limit.
+    // starts at the limit of the variant; games stored before it was kept had 50.
+    #[serde(default = "default_move_limit")]
+    pub move_limit: Option<u32>,
+    // the game as a MorrisGame, see engine.rs
+    #[serde(skip)]
+    pub morris: MorrisCache,
 }
 
 impl OngoingGame {
@@ -181,13 +268,35 @@ impl OngoingGame {
             player_one: String::new(),
             player_two: String::new(),
             whose_turn: String::new(),
+            turn: 1,
             player_one_remaining: 3,
             player_two_remaining: 3,
             board: [[0; 3]; 3],
             moves: Vec::new(),
+            variant: Variant::ThreeMens,
+            ending: None,
+            move_limit: Variant::ThreeMens.rules().move_limit,
+            morris: MorrisCache::default(),
         }
     }
 
+    pub fn with_variant(variant: Variant) -> Result<OngoingGame, GameError> {
+        // new game of a 3x3 variant, remaining pieces follow its rules
+        if !variant.is_grid() {
+            return Err(GameError::UnsupportedVariant {
+                name: variant.name().to_string(),
+            });
+        }
+        let rules = variant.rules();
+        Ok(OngoingGame {
+            player_one_remaining: rules.pieces,
+            player_two_remaining: rules.pieces,
+            move_limit: rules.move_limit,
+            variant,
+            ..OngoingGame::new()
+        })
+    }
+
     pub fn flatten_board(&self) -> String {
         let mut board_flattened = String::new();
         for row in self.board {
@@ -198,6 +307,17 @@ impl OngoingGame {
         return board_flattened;
     }
 
+    pub fn cells(&self) -> [u8; 9] {
+        // board flattened row by row, indexed like the points of Board::three_mens
+        let mut cells = [0; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                cells[row * 3 + col] = self.board[row][col];
+            }
+        }
+        cells
+    }
+
     pub fn get_id(&self) -> &String {
         return &self._id;
     }
@@ -207,9 +327,17 @@ impl OngoingGame {
     }
 
     pub fn update_turn(&mut self) {
-        match self.whose_turn == self.player_one {
-            true => self.whose_turn = self.player_two.clone(),
-            false => self.whose_turn = self.player_one.clone(),
+        // flip the piece first and derive the player id from it.
+        // comparing ids breaks down when both players share an id (e.g. local games).
+        match self.turn {
+            1 => {
+                self.turn = 2;
+                self.whose_turn = self.player_two.clone();
+            }
+            _ => {
+                self.turn = 1;
+                self.whose_turn = self.player_one.clone();
+            }
         }
     }
 
@@ -2,7 +2,7 @@ use eframe::egui;
 
 use crate::types::{OngoingGame, Move};
 
-use crate::stupid_bot::StupidBot;
+use crate::bot::{Bot, Difficulty, Registry};
 
 pub struct GameGUI {
     // column, and row
@@ -11,18 +11,73 @@ pub struct GameGUI {
     current_state: OngoingGame,
     winner: char,
     player_mode: u8,
-    bot: StupidBot,
+    bot: Box<dyn Bot>,
+    selected: Option<(usize, usize)>,
 }
 
 impl Default for GameGUI {
     fn default() -> Self {
+        let bot = Registry::default()
+            .by_difficulty(Difficulty::Easy)
+            .expect("an easy bot is registered");
+        // in single player mode the user goes first and the bot is player two
+        let mut current_state = OngoingGame::new();
+        current_state.player_one = String::from("player");
+        current_state.player_two = bot.id().to_string();
+        current_state.whose_turn = current_state.player_one.clone();
         Self {
             moves: Vec::new(),
             state_history: Vec::new(),
-            current_state: OngoingGame::new(),
+            current_state,
             winner: ' ',
+            // 1 plays against the bot, 2 is two users on one screen
             player_mode: 2,
-            bot: StupidBot::new(),
+            bot,
+            selected: None,
+        }
+    }
+}
+
+impl GameGUI {
+    fn click(&mut self, row: usize, col: usize) {
+        // place a piece while any are left in hand,
+        // otherwise the first click picks a piece and the second one moves it.
+        if self.current_state.outcome().is_some() {
+            return;
+        }
+        if self.current_state.remaining(self.current_state.turn) > 0 {
+            if let Ok(new_move) = Move::coord_to_new_move(&row, &col) {
+                if self.current_state.apply(new_move).is_ok() {
+                    self.bot_turn();
+                }
+            }
+            return;
+        }
+        match self.selected.take() {
+            Some((old_row, old_col)) => {
+                if let Ok(new_move) = Move::coord_to_move(&old_row, &old_col, &row, &col) {
+                    if self.current_state.apply(new_move).is_ok() {
+                        self.bot_turn();
+                    }
+                }
+            }
+            None => {
+                if self.current_state.board[row][col] == self.current_state.turn {
+                    self.selected = Some((row, col));
+                }
+            }
+        }
+    }
+
+    fn bot_turn(&mut self) {
+        // in single player mode the bot answers every move of the user
+        if self.player_mode != 1 || self.current_state.outcome().is_some() {
+            return;
+        }
+        if self.current_state.whose_turn == self.bot.id() {
+            if let Err(e) = self.bot.make_move(&mut self.current_state) {
+                println!("Bot had an error making a move: {}", e);
+            }
         }
     }
 }
@@ -37,6 +92,13 @@ impl eframe::App for GameGUI {
                 ui.monospace("3. When there is no piece left to play, player can move pieces to connected and unoccupied coordinates.");
                 ui.monospace("4. When a player has three pieces in one line, that player wins.");
                 ui.monospace("5. When a player has no more piece to move, that player loses.");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.player_mode, 2, "Two players");
+                    // the bot takes its turn right away if it is to move
+                    if ui.radio_value(&mut self.player_mode, 1, "Against the bot").clicked() {
+                        self.bot_turn();
+                    }
+                });
             });
         });
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -55,7 +117,7 @@ impl eframe::App for GameGUI {
                                 .min_size(egui::Vec2::new(50.0, 50.0)),
                             );
                             if response.clicked() {
-                                self.current_state.board[row][col] += 1;
+                                self.click(row, col);
                             }
                             positions.push(response.rect.center());
                         }
@@ -0,0 +1,97 @@
+use crate::rules::Variant;
+
+// Zobrist keys for 3x3 positions.
+// A position hash is the xor of the keys of everything in it:
+// each piece on a point, pieces left in hand, the player to move and the variant.
+// Keys come from a fixed seed so hashes are the same on every run and can be stored.
+
+pub const POINTS: usize = 9;
+// more pieces in hand than any 3x3 variant has
+pub const MAX_IN_HAND: usize = 16;
+const SEED: u64 = 0x3d6f_2a9c_5b1e_8047;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+pub struct Keys {
+    pub pieces: [[u64; POINTS]; 2],
+    pub in_hand: [[u64; MAX_IN_HAND]; 2],
+    // xor-ed in when player two is to move
+    pub turn: u64,
+    pub variants: [u64; 16],
+}
+
+const fn build_keys() -> Keys {
+    let mut keys = Keys {
+        pieces: [[0; POINTS]; 2],
+        in_hand: [[0; MAX_IN_HAND]; 2],
+        turn: 0,
+        variants: [0; 16],
+    };
+    let mut state = SEED;
+    let mut player = 0;
+    while player < 2 {
+        let mut point = 0;
+        while point < POINTS {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys.pieces[player][point] = key;
+            point += 1;
+        }
+        let mut count = 0;
+        while count < MAX_IN_HAND {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys.in_hand[player][count] = key;
+            count += 1;
+        }
+        player += 1;
+    }
+    let (next, key) = splitmix64(state);
+    state = next;
+    keys.turn = key;
+    let mut variant = 0;
+    while variant < 16 {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys.variants[variant] = key;
+        variant += 1;
+    }
+    keys
+}
+
+pub static KEYS: Keys = build_keys();
+
+pub fn variant_key(variant: Variant) -> u64 {
+    // by position in Variant::all, so new variants must be added at the end
+    let index = Variant::all().iter().position(|&v| v == variant).unwrap_or(0);
+    KEYS.variants[index % 16]
+}
+
+pub fn in_hand_key(player: usize, count: u8) -> u64 {
+    // counts no 3x3 variant reaches share the last key instead of indexing past the table,
+    // so broken games still hash and illegalities() can report them
+    KEYS.in_hand[player][(count as usize).min(MAX_IN_HAND - 1)]
+}
+
+pub fn hash(pieces: [u16; 2], in_hand: [u8; 2], turn: u8, variant: Variant) -> u64 {
+    // full hash of a position, Position keeps it up to date move by move
+    let mut hash = variant_key(variant);
+    for player in 0..2 {
+        for point in 0..POINTS {
+            if pieces[player] >> point & 1 == 1 {
+                hash ^= KEYS.pieces[player][point];
+            }
+        }
+        hash ^= in_hand_key(player, in_hand[player]);
+    }
+    if turn == 2 {
+        hash ^= KEYS.turn;
+    }
+    hash
+}
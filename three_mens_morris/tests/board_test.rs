@@ -0,0 +1,55 @@
+use three_mens_morris::board::Board;
+use three_mens_morris::referee::Referee;
+use three_mens_morris::types::OngoingGame;
+
+#[test]
+fn test_three_mens_adjacency_matches_simulator() {
+    // legal_move_tree from three_mens_morris_v2/simulator/rules.py
+    let legal_move_tree: [&[usize]; 9] = [
+        &[1, 3, 4],
+        &[0, 2, 4],
+        &[1, 4, 5],
+        &[0, 4, 6],
+        &[0, 1, 2, 3, 5, 6, 7, 8],
+        &[2, 4, 8],
+        &[3, 4, 7],
+        &[4, 6, 8],
+        &[4, 5, 7],
+    ];
+    let board = Board::three_mens();
+    for (point, neighbours) in legal_move_tree.iter().enumerate() {
+        assert_eq!(board.neighbours(point), *neighbours);
+    }
+}
+
+#[test]
+fn test_edge_to_edge_diagonals_are_not_valid_moves() {
+    assert!(!Referee::is_valid_move(0, 1, 1, 0));
+    assert!(!Referee::is_valid_move(1, 2, 0, 1));
+    assert!(!Referee::is_valid_move(2, 1, 1, 0));
+    assert!(!Referee::is_valid_move(1, 2, 2, 1));
+    assert!(!Referee::is_valid_move(0, 0, 0, 2));
+    assert!(Referee::is_valid_move(0, 0, 1, 1));
+    assert!(Referee::is_valid_move(1, 1, 2, 0));
+}
+
+#[test]
+fn test_point_names() {
+    let board = Board::three_mens();
+    assert_eq!(board.point_name(0), Some("a1"));
+    assert_eq!(board.point_name(5), Some("c2"));
+    assert_eq!(board.point_index("B3"), Some(7));
+    assert_eq!(board.point_index("d1"), None);
+}
+
+#[test]
+fn test_checkmate_positions_follow_lines() {
+    let mut state = OngoingGame::new();
+    state.board = [[1, 0, 2], [0, 1, 2], [0, 0, 0]];
+    let positions = Referee::get_checkmate_positions(&state).unwrap();
+    // both players threaten c3, player one on the diagonal and player two on column c
+    assert_eq!(positions, vec![(1, 2, 2), (2, 2, 2)]);
+
+    state.board[1][1] = 4;
+    assert!(Referee::get_checkmate_positions(&state).is_err());
+}
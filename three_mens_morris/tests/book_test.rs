@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use three_mens_morris::book::{key_to_game, layer, parse_key, to_key, Book};
+use three_mens_morris::book_bot::{BookBot, Choice};
+use three_mens_morris::bot::Bot;
+use three_mens_morris::error::GameError;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::{Move, OngoingGame};
+
+// always plays c3, so the fallback's move can be told apart from the book's
+struct C3Bot;
+
+impl Bot for C3Bot {
+    fn id(&self) -> &str {
+        "c3-bot"
+    }
+
+    fn name(&self) -> &str {
+        "c3"
+    }
+
+    fn choose_move(&self, _: &OngoingGame) -> Result<Move, GameError> {
+        "c3".parse()
+    }
+}
+
+fn simulator_book() -> Book {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../three_mens_morris_v2/simulator/weight.json"
+    );
+    Book::load(Path::new(path)).unwrap()
+}
+
+#[test]
+fn test_book_keys() {
+    assert_eq!(parse_key("01_2").unwrap(), [0b11, 0b100]);
+    assert_eq!(parse_key("_").unwrap(), [0, 0]);
+    assert_eq!(to_key([0b11, 0b100], 3), "01_2");
+    assert_eq!(to_key([0b1_0001_1000, 0b1010_0010], 8), "348_157-");
+
+    let game = key_to_game("01_2", 3).unwrap();
+    assert_eq!(game.to_fen(), "oox/.../... 2 1 2");
+    assert_eq!(layer(&game), Some(3));
+    let game = key_to_game("034_157-", 8).unwrap();
+    assert_eq!(game.to_fen(), "ox./oox/.x. 1 0 0");
+
+    for (key, layer) in [
+        ("01_2", 2),
+        ("012", 3),
+        ("0_0", 2),
+        ("9_", 1),
+        ("034_157", 8),
+    ] {
+        assert!(
+            matches!(key_to_game(key, layer), Err(GameError::InvalidBook { .. })),
+            "{} at layer {}",
+            key,
+            layer
+        );
+    }
+}
+
+#[test]
+fn test_book_positions() {
+    let book = simulator_book();
+    assert_eq!(book.len(), 5646);
+    let root = book.lookup(&OngoingGame::new()).unwrap();
+    assert_eq!(root.layer, 0);
+    // player one wins most games of the simulator
+    assert!(root.win_rate().unwrap() > 0.5);
+
+    // games of every key find their node again; the history tells layer 8 from layer 6
+    let mut game = OngoingGame::new();
+    for new_move in ["b2", "a1", "c3", "a3", "a2", "c2"] {
+        game.apply(new_move.parse().unwrap()).unwrap();
+        assert_eq!(book.lookup(&game).unwrap().layer, game.moves.len() as u32);
+    }
+    assert_eq!(book.moves(&game).len(), game.legal_moves().len());
+    assert!(book
+        .lookup(&OngoingGame::with_variant(Variant::Achi).unwrap())
+        .is_none());
+}
+
+#[test]
+fn test_book_bot() {
+    let bot = BookBot::new("book-bot", simulator_book());
+    // the centre leaves player two the fewest wins
+    assert_eq!(
+        bot.choose_move(&OngoingGame::new()).unwrap().to_string(),
+        "b2"
+    );
+
+    let mut by_visits = BookBot::new("book-bot", simulator_book());
+    by_visits.choice = Choice::Visits;
+    let new_move = by_visits.book_move(&OngoingGame::new()).unwrap();
+    assert!(OngoingGame::new().legal_moves().contains(&new_move));
+
+    // out of the book the fallback plays
+    let bot = BookBot::with_fallback("book-bot", simulator_book(), Box::new(C3Bot));
+    let achi = OngoingGame::with_variant(Variant::Achi).unwrap();
+    assert_eq!(bot.book_move(&achi), None);
+    assert_eq!(bot.choose_move(&achi).unwrap().to_string(), "c3");
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use three_mens_morris::bot::{Bot, BotFiles, Difficulty, Registry};
+use three_mens_morris::error::GameError;
+use three_mens_morris::types::{Move, OngoingGame};
+
+// plays the first legal move, to check registering a bot from outside the crate
+struct FirstBot {
+    id: String,
+}
+
+impl Bot for FirstBot {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "first"
+    }
+
+    fn choose_move(&self, game: &OngoingGame) -> Result<Move, GameError> {
+        game.legal_moves()
+            .first()
+            .cloned()
+            .ok_or(GameError::NoLegalMove { player: game.turn })
+    }
+}
+
+#[test]
+fn test_registry_lookups() {
+    let registry = Registry::default();
+    assert_eq!(registry.names(), ["random", "mcts", "search"]);
+    assert_eq!(registry.difficulty("mcts"), Some(Difficulty::Medium));
+    assert_eq!(registry.by_name("search").unwrap().name(), "search");
+    assert_eq!(
+        registry.by_difficulty(Difficulty::Easy).unwrap().name(),
+        "random"
+    );
+    // user input: names and difficulties, any case
+    assert_eq!(registry.find(" Hard ").unwrap().name(), "search");
+    assert_eq!(registry.find("mcts").unwrap().name(), "mcts");
+    assert!(matches!(
+        registry.find("deep blue"),
+        Err(GameError::UnknownBot { .. })
+    ));
+
+    // the bot of a stored game is found by its player id
+    let mut game = OngoingGame::new();
+    game.player_two = registry.find("medium").unwrap().id().to_string();
+    assert_eq!(registry.in_game(&game).unwrap().name(), "mcts");
+    assert!(registry.in_game(&OngoingGame::new()).is_none());
+}
+
+#[test]
+fn test_registered_bot_plays() {
+    let mut registry = Registry::default();
+    registry.register("first", Difficulty::Easy, |id| {
+        Box::new(FirstBot { id: id.to_string() })
+    });
+    let bot = registry.find("first").unwrap();
+    assert_eq!(bot.id(), "bot-first");
+
+    let mut game = OngoingGame::new();
+    game.player_one = bot.id().to_string();
+    game.whose_turn = bot.id().to_string();
+    // choosing leaves the game as it was
+    let new_move = bot.choose_move(&game).unwrap();
+    assert!(game.moves.is_empty());
+    assert_eq!(new_move.to_string(), "a1");
+
+    bot.make_move(&mut game).unwrap();
+    assert_eq!(game.moves, [new_move]);
+    // not its turn any more
+    assert!(bot.make_move(&mut game).is_err());
+}
+
+#[test]
+fn test_registry_with_files() {
+    // the simulator's book loads, a missing weights file leaves the network bot out
+    let simulator =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../three_mens_morris_v2/simulator");
+    let files = BotFiles {
+        book: Some(simulator.join("weight.json")),
+        network: Some(simulator.join("missing.json")),
+    };
+    let registry = Registry::with_files(&files);
+    assert_eq!(registry.names(), ["random", "mcts", "search", "book"]);
+    assert_eq!(registry.difficulty("book"), Some(Difficulty::Hard));
+    assert_eq!(registry.find("book").unwrap().id(), "bot-book");
+
+    assert_eq!(
+        Registry::with_files(&BotFiles::default()).names(),
+        Registry::default().names()
+    );
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use three_mens_morris::board::Board;
+use three_mens_morris::definition::BoardDefinition;
+use three_mens_morris::engine::{Outcome, WinReason};
+use three_mens_morris::error::GameError;
+use three_mens_morris::morris::{MorrisGame, MorrisMove};
+
+const TRIANGLE: &str = r#"{
+    "name": "triangle",
+    "pieces": 2,
+    "points": [{"name": "a"}, {"name": "b", "x": 4, "y": 1}, {"name": "c"}, {"name": "d"}],
+    "edges": [["a", "b"], ["b", "c"], ["c", "a"], ["c", "d"]],
+    "lines": [["a", "b"], ["c", "d"]]
+}"#;
+
+#[test]
+fn test_load_toml_file() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("boards/nine_holes.toml");
+    let rules = BoardDefinition::load(&path).unwrap().to_rules().unwrap();
+    let nine_holes = Board::nine_holes();
+    assert_eq!(rules.board.lines, nine_holes.lines);
+    for point in 0..9 {
+        assert_eq!(rules.board.neighbours(point), nine_holes.neighbours(point));
+    }
+}
+
+#[test]
+fn test_play_custom_board() {
+    let definition = BoardDefinition::from_json(TRIANGLE).unwrap();
+    let mut game = MorrisGame::from_definition(&definition).unwrap();
+    assert_eq!(game.board().points[0].x, 0);
+    assert_eq!(game.board().points[1].x, 4);
+    assert_eq!(game.in_hand, [2, 2]);
+
+    game.play("a").unwrap();
+    game.play("c").unwrap();
+    game.play("b").unwrap();
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Win {
+            player: 1,
+            reason: WinReason::ThreeInARow
+        })
+    );
+}
+
+#[test]
+fn test_invalid_definitions() {
+    let with = |from: &str, to: &str| {
+        BoardDefinition::from_json(&TRIANGLE.replace(from, to))
+            .unwrap()
+            .to_rules()
+            .unwrap_err()
+    };
+    assert_eq!(
+        with(r#"["c", "d"]]"#, r#"["c", "e"]]"#),
+        GameError::UnknownPoint {
+            name: String::from("e")
+        }
+    );
+    assert_eq!(
+        with(r#", ["c", "d"]],"#, "],"),
+        GameError::DisconnectedBoard {
+            point: String::from("d")
+        }
+    );
+    assert_eq!(
+        with(r#"{"name": "d"}"#, r#"{"name": "a"}"#),
+        GameError::DuplicatePoint {
+            name: String::from("a")
+        }
+    );
+    assert!(matches!(
+        with(r#""pieces": 2"#, r#""pieces": 5"#),
+        GameError::InvalidDefinition { .. }
+    ));
+    assert!(matches!(
+        BoardDefinition::from_toml("name = 3"),
+        Err(GameError::InvalidDefinition { .. })
+    ));
+}
+
+#[test]
+fn test_point_names_fit_notation() {
+    // notation could not tell a point called "box" from "b" capturing "o"
+    for name in ["x1", "box", "a-1", "", "b 2"] {
+        let definition =
+            TRIANGLE.replace(r#"{"name": "d"}"#, &format!(r#"{{"name": "{}"}}"#, name));
+        let definition = definition.replace(r#"["c", "d"]"#, &format!(r#"["c", "{}"]"#, name));
+        assert!(matches!(
+            BoardDefinition::from_json(&definition).unwrap().to_rules(),
+            Err(GameError::InvalidDefinition { .. })
+        ));
+    }
+
+    // names without them round-trip through notation, captures included
+    let definition = BoardDefinition::from_json(&TRIANGLE.replace(r#""b""#, r#""b2""#)).unwrap();
+    let game = MorrisGame::from_definition(&definition).unwrap();
+    let board = game.board();
+    for input in ["b2", "a-b2", "cxb2", "b2-cxa"] {
+        let new_move = MorrisMove::parse(board, input).unwrap();
+        assert_eq!(new_move.notation(board), input);
+    }
+}
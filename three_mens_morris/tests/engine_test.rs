@@ -0,0 +1,292 @@
+use three_mens_morris::engine::{DrawReason, GameResult, Outcome, WinReason};
+use three_mens_morris::error::GameError;
+use three_mens_morris::types::{GameHistory, Move, OngoingGame};
+
+fn play(state: &mut OngoingGame, moves: &[&str]) {
+    for str_move in moves {
+        let new_move = Move::string_to_move(&str_move.to_string()).unwrap();
+        state.apply(new_move).unwrap();
+    }
+}
+
+#[test]
+fn test_legal_moves_on_empty_board() {
+    let state = OngoingGame::new();
+    assert_eq!(state.legal_moves().len(), 9);
+}
+
+#[test]
+fn test_apply_updates_state() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    state.whose_turn = String::from("one");
+    play(&mut state, &["b2"]);
+
+    assert_eq!(state.board[1][1], 1);
+    assert_eq!(state.player_one_remaining, 2);
+    assert_eq!(state.turn, 2);
+    assert_eq!(state.whose_turn, "two");
+    assert_eq!(state.moves.len(), 1);
+    assert_eq!(state.legal_moves().len(), 8);
+}
+
+#[test]
+fn test_apply_rejects_invalid_moves() {
+    let mut state = OngoingGame::new();
+    play(&mut state, &["b2"]);
+    // occupied
+    assert_eq!(
+        state.apply(Move::coord_to_new_move(&1, &1).unwrap()),
+        Err(GameError::PlaceOccupied { row: 1, col: 1 })
+    );
+    // moving before all pieces are played
+    assert_eq!(
+        state.apply(Move::coord_to_move(&1, &1, &0, &0).unwrap()),
+        Err(GameError::NotPlayedAllPieces { player: 2 })
+    );
+
+    play(&mut state, &["a1", "c1", "a3", "b1", "c3"]);
+    // player one has no piece left to place
+    assert_eq!(
+        state.apply(Move::coord_to_new_move(&1, &2).unwrap()),
+        Err(GameError::MaxPiecePlayed { player: 1 })
+    );
+    // piece belongs to player two
+    assert_eq!(
+        state.apply(Move::coord_to_move(&0, &0, &1, &0).unwrap()),
+        Err(GameError::IncorrectOwnership { player: 1, row: 0, col: 0 })
+    );
+    // b1 and a2 are not connected
+    assert_eq!(
+        state.apply(Move::coord_to_move(&0, &1, &1, &0).unwrap()),
+        Err(GameError::NotAdjacent { row: 0, col: 1, new_row: 1, new_col: 0 })
+    );
+}
+
+#[test]
+fn test_apply_as_checks_player() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    state.whose_turn = String::from("one");
+    let new_move = Move::coord_to_new_move(&0, &0).unwrap();
+
+    assert_eq!(
+        state.apply_as("two", new_move.clone()),
+        Err(GameError::NotYourTurn)
+    );
+    assert_eq!(
+        state.apply_as("three", new_move.clone()),
+        Err(GameError::PlayerNotInGame { player_id: String::from("three") })
+    );
+    assert_eq!(state.apply_as("one", new_move), Ok(()));
+}
+
+#[test]
+fn test_invalid_notation_reports_position() {
+    assert_eq!(
+        Move::string_to_move(&String::from("a4")),
+        Err(GameError::InvalidNotation { input: String::from("a4"), position: 1 })
+    );
+    assert_eq!(
+        Move::string_to_move(&String::from("a1b")),
+        Err(GameError::InvalidNotation { input: String::from("a1b"), position: 3 })
+    );
+    assert_eq!(
+        Move::coord_to_new_move(&3, &0),
+        Err(GameError::OutOfBoard { row: 3, col: 0 })
+    );
+}
+
+#[test]
+fn test_undo_restores_state() {
+    let mut state = OngoingGame::new();
+    play(&mut state, &["b2", "a1", "c1", "a3", "b1", "c3"]);
+    let board_before = state.board;
+    play(&mut state, &["c1c2"]);
+    assert_ne!(state.board, board_before);
+
+    let undone = state.undo().unwrap();
+    assert_eq!(undone, Move::coord_to_move(&0, &2, &1, &2).unwrap());
+    assert_eq!(state.board, board_before);
+    assert_eq!(state.turn, 1);
+
+    while state.undo().is_some() {}
+    assert_eq!(state.board, [[0; 3]; 3]);
+    assert_eq!(state.player_one_remaining, 3);
+    assert_eq!(state.player_two_remaining, 3);
+    assert_eq!(state.turn, 1);
+}
+
+#[test]
+fn test_queries_follow_direct_edits() {
+    // the cached rules view must not outlive changes made through the public fields
+    let mut state = OngoingGame::new();
+    play(&mut state, &["b2", "a1"]);
+    assert_eq!(state.legal_moves().len(), 7);
+    state.board[2][2] = 1;
+    state.player_one_remaining -= 1;
+    assert_eq!(state.legal_moves().len(), 6);
+
+    let mut copy = state.clone();
+    copy.undo();
+    assert_eq!(copy.legal_moves().len(), 7);
+    assert_eq!(state.legal_moves().len(), 6);
+}
+
+#[test]
+fn test_outcome_three_in_a_row() {
+    let mut state = OngoingGame::new();
+    play(&mut state, &["a1", "b1", "a2", "b2"]);
+    assert_eq!(state.outcome(), None);
+    play(&mut state, &["a3"]);
+    assert_eq!(
+        state.outcome(),
+        Some(Outcome::Win { player: 1, reason: WinReason::ThreeInARow })
+    );
+    assert!(state.legal_moves().is_empty());
+    assert_eq!(
+        state.apply(Move::coord_to_new_move(&2, &2).unwrap()),
+        Err(GameError::GameOver)
+    );
+}
+
+#[test]
+fn test_threefold_repetition_is_a_draw() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    play(&mut state, &["b2", "a1", "c1", "a3", "b1", "c3"]);
+    play(&mut state, &["c1c2", "a3a2", "c2c1", "a2a3"]);
+    play(&mut state, &["c1c2", "a3a2", "c2c1"]);
+    assert_eq!(state.outcome(), None);
+    assert!(GameHistory::from_game(&state).is_none());
+
+    play(&mut state, &["a2a3"]);
+    assert_eq!(
+        state.outcome(),
+        Some(Outcome::Draw {
+            reason: DrawReason::Repetition
+        })
+    );
+    let history = GameHistory::from_game(&state).unwrap();
+    assert_eq!(history.winner, "");
+    assert_eq!(history.result.unwrap().winner(), None);
+    assert_eq!(history.result.unwrap().plies, 14);
+    assert_eq!(history.moves.len(), 14);
+}
+
+#[test]
+fn test_result_of_won_game() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    assert_eq!(state.result(), None);
+    play(&mut state, &["a1", "b1", "a2", "b2", "a3"]);
+    assert_eq!(
+        state.result(),
+        Some(GameResult {
+            outcome: Outcome::Win {
+                player: 1,
+                reason: WinReason::ThreeInARow
+            },
+            plies: 5
+        })
+    );
+    assert_eq!(GameHistory::from_game(&state).unwrap().winner, "one");
+}
+
+#[test]
+fn test_resignation_ends_game() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    play(&mut state, &["b2"]);
+    assert_eq!(
+        state.resign("three"),
+        Err(GameError::PlayerNotInGame {
+            player_id: String::from("three")
+        })
+    );
+    let result = state.resign("two").unwrap();
+    assert_eq!(
+        result.outcome,
+        Outcome::Win {
+            player: 1,
+            reason: WinReason::Resignation
+        }
+    );
+    assert_eq!(result.plies, 1);
+    assert_eq!(state.result(), Some(result));
+    assert_eq!(
+        state.apply(Move::coord_to_new_move(&0, &0).unwrap()),
+        Err(GameError::GameOver)
+    );
+    assert_eq!(state.time_out("one"), Err(GameError::GameOver));
+    assert!(state.legal_moves().is_empty());
+    assert_eq!(GameHistory::from_game(&state).unwrap().winner, "one");
+}
+
+#[test]
+fn test_undo_resumes_timed_out_game() {
+    let mut state = OngoingGame::new();
+    state.player_one = String::from("one");
+    state.player_two = String::from("two");
+    play(&mut state, &["b2", "a1"]);
+    state.time_out("one").unwrap();
+    assert!(state.legal_moves().is_empty());
+
+    // taking back the last move takes back the time out with it
+    assert_eq!(state.undo(), Some(Move::coord_to_new_move(&0, &0).unwrap()));
+    assert_eq!(state.result(), None);
+    assert_eq!(state.legal_moves().len(), 8);
+    assert_eq!(state.validate(&Move::coord_to_new_move(&0, &0).unwrap()), Ok(()));
+}
+
+#[test]
+fn test_history_reads_old_documents() {
+    // first format: (column, row) points and no result, slides as two points
+    let old = r#"{"_id": "g1", "player_one": "one", "player_two": "two", "winner": "two",
+        "moves": [["b", "2"], ["a", "1"], ["c", "1"], ["a", "3"], ["b", "1"], ["c", "3"],
+                  ["c", "1"], ["c", "2"], ["a", "3"], ["a", "2"]]}"#;
+    let history: GameHistory = serde_json::from_str(old).unwrap();
+    assert_eq!(history.moves.len(), 8);
+    assert_eq!(history.moves[6].to_string(), "c1-c2");
+    assert_eq!(history.moves[7].to_string(), "a3-a2");
+    // the game went on after these moves, so the result stays unknown
+    let mut replayed = OngoingGame::new();
+    for new_move in &history.moves {
+        replayed.apply(new_move.clone()).unwrap();
+    }
+    assert_eq!(history.result, None);
+
+    // later format: moves, but a draw flag instead of a result
+    let mut state = OngoingGame::new();
+    play(&mut state, &["a1", "b1", "a2", "b2", "a3"]);
+    let moves = serde_json::to_value(&state.moves).unwrap();
+    let old = serde_json::json!({"_id": "g2", "player_one": "one", "player_two": "two",
+        "winner": "one", "draw": false, "moves": moves});
+    let history: GameHistory = serde_json::from_value(old).unwrap();
+    assert_eq!(history.result, state.result());
+
+    // and current histories round-trip
+    let stored = serde_json::to_string(&GameHistory::from_game(&state).unwrap()).unwrap();
+    let history: GameHistory = serde_json::from_str(&stored).unwrap();
+    assert_eq!(history.result, state.result());
+    assert_eq!(history.moves, state.moves);
+
+    let odd = old_points(&[("a", "1"); 7]);
+    assert!(serde_json::from_str::<GameHistory>(&odd).is_err());
+}
+
+fn old_points(points: &[(&str, &str)]) -> String {
+    let points: Vec<String> = points
+        .iter()
+        .map(|(col, row)| format!(r#"["{}", "{}"]"#, col, row))
+        .collect();
+    format!(
+        r#"{{"_id": "g3", "player_one": "one", "player_two": "two", "winner": "", "moves": [{}]}}"#,
+        points.join(", ")
+    )
+}
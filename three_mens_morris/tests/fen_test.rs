@@ -0,0 +1,95 @@
+use three_mens_morris::engine::{DrawReason, Outcome};
+use three_mens_morris::error::GameError;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::OngoingGame;
+
+fn played(moves: &[&str]) -> OngoingGame {
+    let mut game = OngoingGame::new();
+    for str_move in moves {
+        game.apply(str_move.parse().unwrap()).unwrap();
+    }
+    game
+}
+
+fn reason(fen: &str) -> String {
+    match OngoingGame::from_fen(fen) {
+        Err(GameError::InvalidFen { reason, .. }) => reason,
+        other => panic!("{} was read as {:?}", fen, other),
+    }
+}
+
+#[test]
+fn test_fen_of_played_games() {
+    assert_eq!(OngoingGame::new().to_fen(), ".../.../... 1 3 3 0 0");
+    let game = played(&["b2", "a1", "c3", "a3", "a2", "c1", "c3-b3"]);
+    assert_eq!(game.to_fen(), "x.x/oo./xo. 2 0 0 1 7");
+
+    let read = OngoingGame::from_fen(&game.to_fen()).unwrap();
+    assert_eq!(read.board, game.board);
+    assert_eq!(read.turn, 2);
+    assert_eq!(read.zobrist(), game.zobrist());
+    // no history, so no counters
+    assert_eq!(read.to_fen(), "x.x/oo./xo. 2 0 0");
+
+    let achi = OngoingGame::from_fen("o../.x./... 1 3 3 achi 0 2").unwrap();
+    assert_eq!(achi.variant, Variant::Achi);
+    assert_eq!(achi.to_fen(), "o../.x./... 1 3 3 achi");
+    assert_eq!(achi.legal_moves().len(), 7);
+}
+
+#[test]
+fn test_invalid_fen_gives_the_reason() {
+    assert!(reason("o../.x./...").contains("found 1 fields"));
+    assert!(reason("o../.x. 1 2 2").contains("3 rows"));
+    assert!(
+        reason("o../.xx/... 1 2 2").contains("player 2 has 2 pieces on the board and 2 in hand instead of 3")
+    );
+    assert!(reason("o../.q./... 1 2 2").contains("unknown piece q"));
+    assert!(reason("o../.x./... 3 2 2").contains("must be 1 or 2"));
+    assert!(reason("o../.x./... 2 2 2").contains("player 2 cannot be to move"));
+    assert!(reason("o../.x./... 1 2 2 chess").contains("unknown variant chess"));
+    assert!(reason("o../.x./... 1 2 2 0 3").contains("after 3 plies"));
+    assert!(reason("x.x/oo./xo. 2 0 0 9 7").contains("do not fit"));
+    assert!(matches!(
+        OngoingGame::from_fen(".../.../... 1 9 9 nine_mens"),
+        Err(GameError::UnsupportedVariant { .. })
+    ));
+}
+
+#[test]
+fn test_move_limit_in_fen() {
+    let mut game = OngoingGame::from_fen(".../.../... 1 3 3 limit=3").unwrap();
+    assert_eq!(game.move_limit, Some(3));
+    for str_move in ["b2", "a1", "c1", "a3", "b1", "c3", "c1c2", "a3a2"] {
+        game.apply(str_move.parse().unwrap()).unwrap();
+    }
+    assert_eq!(game.outcome(), None);
+    assert_eq!(game.to_fen(), "xo./xoo/..x 1 0 0 2 8 limit=3");
+    game.apply("c2c1".parse().unwrap()).unwrap();
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Draw {
+            reason: DrawReason::MoveLimit
+        })
+    );
+
+    let unlimited = OngoingGame::from_fen("o../.x./... 1 3 3 achi limit=none").unwrap();
+    assert_eq!(unlimited.move_limit, None);
+    assert_eq!(unlimited.to_fen(), "o../.x./... 1 3 3 achi limit=none");
+    assert_eq!(OngoingGame::new().move_limit, Some(50));
+    assert!(reason("o../.x./... 1 2 2 limit=lots").contains("move limit must be a number"));
+}
+
+#[test]
+fn test_fen_with_requested_variant() {
+    // the counts only fit achi, which the string does not name
+    assert!(reason(".../.../... 1 4 4").contains("4 in hand"));
+    let achi = OngoingGame::from_fen_with_variant(".../.../... 1 4 4", Variant::Achi).unwrap();
+    assert_eq!(achi.variant, Variant::Achi);
+    assert_eq!(achi.legal_moves().len(), 9);
+    assert!(OngoingGame::from_fen_with_variant(".../.../... 1 4 4 achi", Variant::Achi).is_ok());
+
+    let tapatan = OngoingGame::from_fen_with_variant("o../.x./... 1 2 2 tapatan", Variant::Achi);
+    assert!(matches!(tapatan, Err(GameError::InvalidFen { reason, .. })
+        if reason == "position is for tapatan but the game is achi"));
+}
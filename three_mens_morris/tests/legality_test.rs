@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+
+use three_mens_morris::legality::Illegality;
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::OngoingGame;
+
+fn position(fen: &str) -> Position {
+    // boards written out by hand, without the checks of from_fen
+    let mut game = OngoingGame::new();
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    for (index, c) in fields[0].replace('/', "").chars().enumerate() {
+        game.board[index / 3][index % 3] = match c {
+            'o' => 1,
+            'x' => 2,
+            _ => 0,
+        };
+    }
+    game.turn = fields[1].parse().unwrap();
+    game.player_one_remaining = fields[2].parse().unwrap();
+    game.player_two_remaining = fields[3].parse().unwrap();
+    if let Some(name) = fields.get(4) {
+        game.variant = Variant::from_name(name).unwrap();
+    }
+    Position::from_game(&game)
+}
+
+fn reachable(variant: Variant) -> HashSet<u64> {
+    // every position a game gets to, walking forward from the start
+    let start = Position::new(variant);
+    let mut seen = HashSet::from([start.hash()]);
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        for &new_move in position.legal_moves().iter() {
+            let mut next = position;
+            next.apply(new_move);
+            if seen.insert(next.hash()) {
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+#[test]
+fn test_each_inconsistency_has_its_reason() {
+    assert_eq!(position("o../.x./... 1 2 2").illegalities(), vec![]);
+    assert_eq!(
+        position("oo./.x./... 1 2 2").illegalities(),
+        vec![
+            Illegality::PieceCount {
+                player: 1,
+                on_board: 2,
+                in_hand: 2,
+                pieces: 3
+            },
+            Illegality::WrongTurn {
+                turn: 1,
+                placed: [2, 1]
+            }
+        ]
+    );
+    assert_eq!(
+        position("ooo/xxx/... 2 0 0").illegalities(),
+        vec![Illegality::BothWon]
+    );
+    assert_eq!(
+        position("ooo/xx./..x 1 0 0").illegalities(),
+        vec![Illegality::WinnerToMove { player: 1 }]
+    );
+
+    let mut shared = position("o../.x./... 1 2 2");
+    shared.pieces[1] |= 1;
+    assert!(shared
+        .illegalities()
+        .contains(&Illegality::SharedPoint { point: 0 }));
+}
+
+#[test]
+fn test_reachability_needs_the_search() {
+    // player one won with a slide
+    assert_eq!(position("ooo/xx./..x 2 0 0").check_legal(), Ok(()));
+    // looks fine, but without diagonals player one cannot have made the last move
+    let position = position("oox/ox./x.. 2 0 0 nine_holes");
+    assert_eq!(position.illegalities(), vec![]);
+    assert_eq!(position.check_legal(), Err(Illegality::Unreachable));
+}
+
+#[test]
+fn test_reachability_matches_a_forward_walk() {
+    // every position without static problems is reachable exactly when a game gets there.
+    // on the three men's board the static checks already catch everything, nine holes needs the search.
+    let forward = reachable(Variant::NineHoles);
+    let mut unreachable = 0;
+    for cells in 0..3u32.pow(9) {
+        let mut game = OngoingGame::with_variant(Variant::NineHoles).unwrap();
+        let mut rest = cells;
+        for point in 0..9 {
+            game.board[point / 3][point % 3] = (rest % 3) as u8;
+            rest /= 3;
+        }
+        let cells = game.cells();
+        let on_board = |piece: u8| cells.iter().filter(|&&p| p == piece).count() as u8;
+        if on_board(1) > 3 || on_board(2) > 3 {
+            continue;
+        }
+        game.player_one_remaining = 3 - on_board(1);
+        game.player_two_remaining = 3 - on_board(2);
+        for turn in [1, 2] {
+            game.turn = turn;
+            let position = Position::from_game(&game);
+            if !position.illegalities().is_empty() {
+                continue;
+            }
+            let is_reachable = position.is_reachable();
+            assert_eq!(
+                is_reachable,
+                forward.contains(&position.hash()),
+                "{}",
+                game.to_fen()
+            );
+            if !is_reachable {
+                unreachable += 1;
+            }
+        }
+    }
+    assert_eq!(unreachable, 4);
+}
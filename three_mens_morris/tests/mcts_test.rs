@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use three_mens_morris::bot::Bot;
+use three_mens_morris::mcts::{Config, Mcts, Playout};
+use three_mens_morris::mcts_bot::MctsBot;
+use three_mens_morris::types::OngoingGame;
+
+fn config(playout: Playout) -> Config {
+    Config {
+        iterations: 1000,
+        playout,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_mcts_wins_and_blocks() {
+    for playout in [Playout::Random, Playout::Heuristic] {
+        let bot = MctsBot::with_seed("mcts-bot", config(playout), 7);
+        let game = OngoingGame::from_fen("oo./xx./... 1 1 1").unwrap();
+        assert_eq!(bot.choose_move(&game).unwrap().to_string(), "c1");
+        let game = OngoingGame::from_fen("o../xx./o.. 1 1 1").unwrap();
+        assert_eq!(bot.choose_move(&game).unwrap().to_string(), "c2");
+    }
+}
+
+#[test]
+fn test_mcts_counts_every_iteration() {
+    let game = OngoingGame::new();
+    let result = Mcts::with_seed(config(Playout::Random), 1).search(&game);
+    assert_eq!(result.iterations, 1000);
+    assert_eq!(result.moves.len(), 9);
+    assert_eq!(
+        result.moves.iter().map(|stats| stats.visits).sum::<u32>(),
+        1000
+    );
+    assert!(result
+        .moves
+        .iter()
+        .all(|stats| (0.0..=1.0).contains(&stats.value)));
+
+    // the time runs out long before the iterations
+    let limited = Config {
+        iterations: u32::MAX,
+        time: Some(Duration::from_millis(20)),
+        ..Config::default()
+    };
+    let result = Mcts::with_seed(limited, 1).search(&game);
+    assert!(result.iterations > 0 && result.iterations < u32::MAX);
+
+    let over = OngoingGame::from_fen("ooo/xx./x.. 2 0 0").unwrap();
+    assert!(Mcts::new(Config::default()).search(&over).moves.is_empty());
+}
+
+#[test]
+fn test_mcts_seed_and_temperature() {
+    let game = OngoingGame::new();
+    let search = |seed| Mcts::with_seed(config(Playout::Heuristic), seed).search(&game);
+    assert_eq!(search(3), search(3));
+
+    // temperature 0 always plays the most visited move
+    let result = search(3);
+    let mut mcts = Mcts::with_seed(config(Playout::Heuristic), 3);
+    let most = result.moves.iter().map(|stats| stats.visits).max().unwrap();
+    let chosen = mcts.select_move(&result).unwrap();
+    assert!(result
+        .moves
+        .iter()
+        .any(|stats| stats.new_move == chosen && stats.visits == most));
+
+    // a small temperature is nearly 0, the weights must not overflow
+    mcts.config.temperature = 0.01;
+    let chosen = mcts.select_move(&result).unwrap();
+    assert!(result.moves.iter().any(|stats| stats.new_move == chosen));
+    let mut cold = Mcts::with_seed(
+        Config {
+            temperature: 0.01,
+            iterations: 5000,
+            ..Config::default()
+        },
+        3,
+    );
+    let result = cold.search(&game);
+    assert!(cold.select_move(&result).is_some());
+
+    // infinite temperature ignores the visits
+    mcts.config.temperature = f64::INFINITY;
+    let mut chosen: Vec<String> = (0..200)
+        .map(|_| mcts.select_move(&result).unwrap().to_string())
+        .collect();
+    chosen.sort();
+    chosen.dedup();
+    assert_eq!(chosen.len(), 9);
+}
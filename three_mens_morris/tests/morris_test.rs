@@ -0,0 +1,251 @@
+use three_mens_morris::board::Board;
+use three_mens_morris::engine::{DrawReason, Outcome, WinReason};
+use three_mens_morris::error::GameError;
+use three_mens_morris::morris::{MorrisGame, MorrisMove};
+use three_mens_morris::rules::Variant;
+
+// nine men's game in the moving phase with the given pieces, player one to move
+fn nine_mens(player_one: &[&str], player_two: &[&str]) -> MorrisGame {
+    let mut game = MorrisGame::from_variant(Variant::NineMens);
+    game.in_hand = [0, 0];
+    for (player, points) in [(1, player_one), (2, player_two)] {
+        for point in points {
+            let index = game.board().point_index(point).unwrap();
+            game.cells[index] = player;
+        }
+    }
+    game
+}
+
+#[test]
+fn test_nine_mens_board() {
+    let board = Board::nine_mens();
+    assert_eq!(board.len(), 24);
+    assert_eq!(board.lines.len(), 16);
+
+    let d2 = board.point_index("d2").unwrap();
+    let mut neighbours: Vec<&str> = board
+        .neighbours(d2)
+        .iter()
+        .map(|&p| board.point_name(p).unwrap())
+        .collect();
+    neighbours.sort();
+    assert_eq!(neighbours, vec!["b2", "d1", "d3", "f2"]);
+    assert_eq!(board.neighbours(board.point_index("a1").unwrap()).len(), 2);
+}
+
+#[test]
+fn test_mill_requires_removal() {
+    let mut game = MorrisGame::from_variant(Variant::NineMens);
+    for notation in ["a1", "b2", "d1", "d2"] {
+        game.play(notation).unwrap();
+    }
+    assert_eq!(game.play("g1"), Err(GameError::RemovalRequired));
+    assert_eq!(game.play("g7xb2"), Err(GameError::RemovalNotAllowed));
+    game.play("g1xb2").unwrap();
+
+    let b2 = game.board().point_index("b2").unwrap();
+    assert_eq!(game.cells[b2], 0);
+    assert_eq!(game.in_hand, [6, 7]);
+    assert_eq!(game.turn, 2);
+
+    // undo puts the removed piece back
+    game.undo();
+    assert_eq!(game.cells[b2], 2);
+    assert_eq!(game.in_hand, [7, 7]);
+    assert_eq!(game.turn, 1);
+}
+
+#[test]
+fn test_pieces_in_mill_are_protected() {
+    let mut game = nine_mens(&["a4", "b4", "c5", "f2"], &["a1", "d1", "g1", "e4"]);
+    assert_eq!(
+        game.play("c5-c4xa1"),
+        Err(GameError::CannotRemove {
+            point: String::from("a1")
+        })
+    );
+    let c5 = game.board().point_index("c5").unwrap();
+    let c4 = game.board().point_index("c4").unwrap();
+    let e4 = game.board().point_index("e4").unwrap();
+    assert_eq!(game.removable(1), vec![e4]);
+
+    // once every piece is in a mill any of them can go
+    let mut all_in_mill = nine_mens(&["a4", "b4", "c5", "f2"], &["a1", "d1", "g1"]);
+    all_in_mill.play("c5-c4xa1").unwrap();
+
+    let moves = game.legal_moves();
+    assert!(moves.contains(&MorrisMove::slide(c5, c4).removing(e4)));
+    assert!(!moves.contains(&MorrisMove::slide(c5, c4)));
+}
+
+#[test]
+fn test_flying_with_three_pieces() {
+    let mut game = nine_mens(&["a1", "a4", "b2"], &["g7", "f6", "e5", "c5"]);
+    assert!(game.can_fly(1));
+    assert!(!game.can_fly(2));
+    game.play("a1-g1").unwrap();
+    assert_eq!(
+        game.play("g7-a7"),
+        Err(GameError::NotConnected {
+            from: String::from("g7"),
+            to: String::from("a7")
+        })
+    );
+}
+
+#[test]
+fn test_loss_with_two_pieces() {
+    let mut game = nine_mens(&["a1", "d1", "g4"], &["g7", "f6", "e5"]);
+    assert_eq!(game.outcome(), None);
+    game.play("g4-g1xf6").unwrap();
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Win {
+            player: 1,
+            reason: WinReason::TooFewPieces
+        })
+    );
+    assert!(game.legal_moves().is_empty());
+    assert_eq!(game.play("g7-g4"), Err(GameError::GameOver));
+}
+
+#[test]
+fn test_notation_round_trip() {
+    let game = nine_mens(&["a4", "b4", "c5", "f2"], &["a1", "d1", "g1", "e4"]);
+    let board = game.board();
+    for new_move in game.legal_moves() {
+        let notation = new_move.notation(board);
+        assert_eq!(MorrisMove::parse(board, &notation), Ok(new_move));
+    }
+    assert_eq!(
+        MorrisMove::parse(board, "c5-c4xe4").unwrap().notation(board),
+        "c5-c4xe4"
+    );
+    assert_eq!(
+        MorrisMove::parse(board, "b1"),
+        Err(GameError::UnknownPoint {
+            name: String::from("b1")
+        })
+    );
+    assert_eq!(
+        MorrisMove::parse(board, "c5-"),
+        Err(GameError::InvalidNotation {
+            input: String::from("c5-"),
+            position: 3
+        })
+    );
+}
+
+#[test]
+fn test_six_mens_morris() {
+    let board = Board::six_mens();
+    assert_eq!(board.len(), 16);
+    assert_eq!(board.lines.len(), 8);
+    assert!(board.is_adjacent(
+        board.point_index("c1").unwrap(),
+        board.point_index("c2").unwrap()
+    ));
+
+    let mut game = MorrisGame::from_name("six_mens").unwrap();
+    assert_eq!(game.in_hand, [6, 6]);
+    // the joint c1-c2 is not a mill, a side is
+    for notation in ["c1", "a3", "c2", "e3"] {
+        game.play(notation).unwrap();
+    }
+    game.play("b2").unwrap();
+    game.play("a1").unwrap();
+    game.play("d2xe3").unwrap();
+    assert_eq!(game.in_hand, [2, 3]);
+
+    assert_eq!(
+        MorrisGame::from_name("ten_mens").unwrap_err(),
+        GameError::UnknownVariant {
+            name: String::from("ten_mens")
+        }
+    );
+}
+
+#[test]
+fn test_six_mens_has_no_flying() {
+    let mut game = MorrisGame::from_variant(Variant::SixMens);
+    game.in_hand = [0, 0];
+    for (point, player) in [("a1", 1), ("a5", 1), ("b3", 1), ("e5", 2), ("d4", 2), ("c5", 2)] {
+        let index = game.board().point_index(point).unwrap();
+        game.cells[index] = player;
+    }
+    assert!(!game.can_fly(1));
+    assert_eq!(
+        game.play("b3-e1"),
+        Err(GameError::NotConnected {
+            from: String::from("b3"),
+            to: String::from("e1")
+        })
+    );
+    assert_eq!(game.play("b3-a3"), Err(GameError::RemovalRequired));
+    game.play("b3-a3xe5").unwrap();
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Win {
+            player: 1,
+            reason: WinReason::TooFewPieces
+        })
+    );
+}
+
+#[test]
+fn test_twelve_mens_diagonal_mill() {
+    let board = Board::twelve_mens();
+    assert_eq!(board.len(), 24);
+    assert_eq!(board.lines.len(), 20);
+    assert_eq!(board.neighbours(board.point_index("a1").unwrap()).len(), 3);
+    assert_eq!(board.neighbours(board.point_index("b2").unwrap()).len(), 4);
+
+    let mut game = MorrisGame::from_variant(Variant::TwelveMens);
+    assert_eq!(game.in_hand, [12, 12]);
+    for notation in ["a1", "d1", "b2", "d2"] {
+        game.play(notation).unwrap();
+    }
+    assert_eq!(game.play("c3"), Err(GameError::RemovalRequired));
+    game.play("c3xd1").unwrap();
+}
+
+#[test]
+fn test_full_board_is_a_draw() {
+    for variant in [Variant::TwelveMens, Variant::Morabaraba] {
+        let mut game = MorrisGame::from_variant(variant);
+        game.in_hand = [0, 0];
+        for (point, piece) in game.cells.iter_mut().enumerate() {
+            *piece = (point % 2) as u8 + 1;
+        }
+        assert_eq!(
+            game.outcome(),
+            Some(Outcome::Draw {
+                reason: DrawReason::FullBoard
+            })
+        );
+        assert!(game.legal_moves().is_empty());
+    }
+    assert!(!MorrisGame::from_variant(Variant::TwelveMens).rules.flying);
+    assert!(MorrisGame::from_variant(Variant::Morabaraba).rules.flying);
+}
+
+#[test]
+fn test_move_limit_is_a_draw() {
+    let mut game = MorrisGame::from_variant(Variant::ThreeMens);
+    game.set_move_limit(Some(3));
+    for notation in ["b2", "a1", "c1", "a3", "b1", "c3", "c1-c2", "a3-a2"] {
+        game.play(notation).unwrap();
+    }
+    assert_eq!(game.quiet_moves(), 2);
+    assert_eq!(game.outcome(), None);
+    game.play("c2-c1").unwrap();
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Draw {
+            reason: DrawReason::MoveLimit
+        })
+    );
+    // presets keep their own limit
+    assert_eq!(Variant::ThreeMens.rules().move_limit, Some(50));
+}
@@ -0,0 +1,88 @@
+use serde_json::{json, Value};
+use three_mens_morris::bot::Bot;
+use three_mens_morris::error::GameError;
+use three_mens_morris::network::{encode, Network};
+use three_mens_morris::network_bot::NetworkBot;
+use three_mens_morris::types::OngoingGame;
+
+fn zeros(rows: usize, cols: usize) -> Value {
+    json!(vec![vec![0.0; cols]; rows])
+}
+
+fn weights() -> Value {
+    // hidden unit 0 reads point a1, the policy head turns it into a preference for b2
+    // and the value head into a value
+    let mut weights = json!({
+        "fc1.weight": zeros(16, 9),
+        "fc1.bias": vec![0.0; 16],
+        "fc2.weight": zeros(16, 16),
+        "fc2.bias": vec![0.0; 16],
+        "action_head.weight": zeros(9, 16),
+        "action_head.bias": vec![0.0; 9],
+        "value_head.weight": zeros(1, 16),
+        "value_head.bias": vec![0.0; 1],
+    });
+    weights["fc1.weight"][0][0] = json!(1.0);
+    weights["fc2.weight"][0][0] = json!(1.0);
+    weights["action_head.weight"][4][0] = json!(2.0);
+    weights["value_head.weight"][0][0] = json!(1.0);
+    weights
+}
+
+#[test]
+fn test_network_forward() {
+    let network = Network::from_json(&weights().to_string()).unwrap();
+
+    // nothing on a1: uniform policy and an even game
+    let (policy, value) = network.predict(&OngoingGame::new());
+    assert!(policy.iter().all(|&p| (p - 1.0 / 9.0).abs() < 1e-6));
+    assert_eq!(value, 0.0);
+
+    // own piece on a1
+    let game = OngoingGame::from_fen("o../.x./... 1 2 2").unwrap();
+    assert_eq!(
+        encode(&game),
+        [1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0]
+    );
+    let (policy, value) = network.predict(&game);
+    let b2 = 2f32.exp() / (2f32.exp() + 8.0);
+    assert!((policy[4] - b2).abs() < 1e-6);
+    assert!((policy.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert!((value - 1f32.tanh()).abs() < 1e-6);
+
+    // the opponent's piece on a1 is cut off by the ReLU
+    let game = OngoingGame::from_fen("o../.x./..o 2 1 2").unwrap();
+    assert_eq!(encode(&game)[0], -1.0);
+    assert_eq!(network.predict(&game).1, 0.0);
+}
+
+#[test]
+fn test_network_files() {
+    let network = Network::from_json(&weights().to_string()).unwrap();
+    assert_eq!(Network::from_json(&network.to_json()).unwrap(), network);
+    let bytes = network.to_bytes();
+    assert_eq!(bytes.len(), 10 + 602 * 4);
+    assert_eq!(Network::from_bytes(&bytes).unwrap(), network);
+
+    let mut wrong = weights();
+    wrong["fc2.weight"] = zeros(16, 9);
+    assert!(matches!(
+        Network::from_json(&wrong.to_string()),
+        Err(GameError::InvalidNetwork { .. })
+    ));
+    assert!(Network::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+}
+
+#[test]
+fn test_network_bot() {
+    let bot = NetworkBot::new(
+        "network-bot",
+        Network::from_json(&weights().to_string()).unwrap(),
+    );
+    // a piece on a1 makes b2 the favourite target
+    let game = OngoingGame::from_fen("o../x../... 1 2 2").unwrap();
+    assert_eq!(bot.choose_move(&game).unwrap().to_string(), "b2");
+    // without it the policy is flat and the first legal move is played
+    let game = OngoingGame::new();
+    assert_eq!(bot.choose_move(&game).unwrap(), game.legal_moves()[0]);
+}
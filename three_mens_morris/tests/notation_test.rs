@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use three_mens_morris::error::GameError;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::position::Position;
+use three_mens_morris::types::Move;
+
+fn invalid(input: &str, position: usize) -> Result<Move, GameError> {
+    Err(GameError::InvalidNotation {
+        input: input.to_string(),
+        position,
+    })
+}
+
+fn check_round_trip(new_move: &Move) {
+    let printed = new_move.to_string();
+    assert_eq!(printed.parse::<Move>().as_ref(), Ok(new_move), "{}", printed);
+    let indices = new_move.index_notation().unwrap();
+    assert_eq!(indices.parse::<Move>().as_ref(), Ok(new_move), "{}", indices);
+    // without the separator
+    let joined = printed.replace('-', "");
+    assert_eq!(joined.parse::<Move>().as_ref(), Ok(new_move), "{}", joined);
+}
+
+fn walk(start: Position, seen: &mut HashSet<String>) {
+    // every position reachable from the start, each visited once
+    let mut visited = HashSet::from([start.hash()]);
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        for &point_move in position.legal_moves().iter() {
+            let new_move = Position::to_move(&point_move);
+            if seen.insert(new_move.to_string()) {
+                check_round_trip(&new_move);
+            }
+            let mut next = position;
+            next.apply(point_move);
+            if visited.insert(next.hash()) {
+                stack.push(next);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_every_square_and_slide_round_trips() {
+    for from in 0..9 {
+        let place = Move::coord_to_new_move(&(from / 3), &(from % 3)).unwrap();
+        check_round_trip(&place);
+        assert_eq!(place.index_notation().unwrap(), from.to_string());
+        for to in 0..9 {
+            let slide = Move::coord_to_move(&(from / 3), &(from % 3), &(to / 3), &(to % 3)).unwrap();
+            check_round_trip(&slide);
+        }
+    }
+}
+
+#[test]
+fn test_legal_moves_round_trip() {
+    // every legal move of every reachable position of each 3x3 variant
+    for variant in Variant::all().iter().copied().filter(|v| v.is_grid()) {
+        let mut seen = HashSet::new();
+        walk(Position::new(variant), &mut seen);
+        assert!(seen.iter().any(|m| m.contains('-')));
+    }
+}
+
+#[test]
+fn test_forms_agree() {
+    let slide = Move::coord_to_move(&0, &0, &1, &1).unwrap();
+    assert_eq!(slide.to_string(), "a1-b2");
+    assert_eq!(slide.index_notation().unwrap(), "0-4");
+    for input in ["a1-b2", "a1b2", "A1-B2", "0-4", "04", " a1-b2 "] {
+        assert_eq!(input.parse::<Move>(), Ok(slide.clone()), "{}", input);
+    }
+    // a2 is row 2, column a: point 3
+    assert_eq!("a2".parse::<Move>(), "3".parse::<Move>());
+    assert_eq!("c1".parse::<Move>(), "2".parse::<Move>());
+}
+
+#[test]
+fn test_errors_point_at_the_character() {
+    assert_eq!("".parse::<Move>(), invalid("", 0));
+    assert_eq!("d1".parse::<Move>(), invalid("d1", 0));
+    assert_eq!("a0".parse::<Move>(), invalid("a0", 1));
+    assert_eq!("a".parse::<Move>(), invalid("a", 1));
+    // rows and columns in the wrong order
+    assert_eq!("1a".parse::<Move>(), invalid("1a", 1));
+    assert_eq!("9".parse::<Move>(), invalid("9", 0));
+    assert_eq!("a1-".parse::<Move>(), invalid("a1-", 3));
+    assert_eq!("a1-b4".parse::<Move>(), invalid("a1-b4", 4));
+    assert_eq!("a1->b2".parse::<Move>(), invalid("a1->b2", 3));
+    assert_eq!("a1-4".parse::<Move>(), invalid("a1-4", 3));
+    assert_eq!("0-b2".parse::<Move>(), invalid("0-b2", 2));
+    assert_eq!("a1b2c3".parse::<Move>(), invalid("a1b2c3", 4));
+}
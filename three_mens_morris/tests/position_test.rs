@@ -0,0 +1,72 @@
+use three_mens_morris::position::{Masks, Position};
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::{Move, OngoingGame};
+
+fn perft(state: &mut OngoingGame, depth: u32) -> u64 {
+    // same count as Position::perft, through the engine
+    if depth == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    for new_move in state.legal_moves() {
+        state.apply(new_move).unwrap();
+        count += perft(state, depth - 1);
+        state.undo();
+    }
+    count
+}
+
+#[test]
+fn test_masks() {
+    let masks = Masks::of(Variant::ThreeMens);
+    assert_eq!(masks.lines.len(), 8);
+    assert_eq!(masks.adjacency[4], 0b111_101_111);
+    assert_eq!(masks.adjacency[1], 0b000_010_101);
+    assert_eq!(Masks::of(Variant::NineHoles).lines.len(), 6);
+    assert_eq!(Masks::of(Variant::NineHoles).adjacency[4], 0b010_101_010);
+}
+
+#[test]
+fn test_game_round_trip() {
+    let mut state = OngoingGame::new();
+    for str_move in ["b2", "a1", "c1"] {
+        state
+            .apply(Move::string_to_move(&str_move.to_string()).unwrap())
+            .unwrap();
+    }
+    let position = Position::from_game(&state);
+    assert_eq!(position.pieces, [0b000_010_100, 0b000_000_001]);
+    assert_eq!(position.in_hand, [1, 2]);
+    assert_eq!(position.turn, 2);
+
+    let back = position.to_game();
+    assert_eq!(back.board, state.board);
+    assert_eq!(back.turn, state.turn);
+    assert_eq!(Position::from_game(&back), position);
+}
+
+#[test]
+fn test_perft_matches_engine() {
+    for variant in [Variant::ThreeMens, Variant::Achi, Variant::NineHoles] {
+        let mut state = OngoingGame::with_variant(variant).unwrap();
+        let mut position = Position::new(variant);
+        for depth in 0..7 {
+            assert_eq!(position.perft(depth), perft(&mut state, depth), "{:?}", variant);
+        }
+        assert_eq!(position, Position::new(variant));
+    }
+}
+
+#[test]
+fn test_outcome_matches_engine() {
+    let mut state = OngoingGame::new();
+    for str_move in ["a1", "b1", "a2", "b2", "a3"] {
+        state
+            .apply(Move::string_to_move(&str_move.to_string()).unwrap())
+            .unwrap();
+        let position = Position::from_game(&state);
+        assert_eq!(position.outcome(), state.outcome());
+        assert_eq!(position.legal_moves().len(), state.legal_moves().len());
+    }
+    assert_eq!(Position::from_game(&state).winner(), Some(1));
+}
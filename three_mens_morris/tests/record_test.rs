@@ -0,0 +1,137 @@
+use three_mens_morris::engine::{GameResult, Outcome, WinReason};
+use three_mens_morris::error::GameError;
+use three_mens_morris::record::{self, GameRecord};
+use three_mens_morris::types::{GameHistory, OngoingGame};
+
+fn played(moves: &[&str]) -> OngoingGame {
+    let mut game = OngoingGame::new();
+    game.player_one = String::from("alice");
+    game.player_two = String::from("bob");
+    game.whose_turn = game.player_one.clone();
+    for str_move in moves {
+        game.apply(str_move.parse().unwrap()).unwrap();
+    }
+    game
+}
+
+#[test]
+fn test_record_round_trip() {
+    let game = played(&["b2", "a2", "a1", "c3", "c1", "a3", "b2-b1"]);
+    let history = GameHistory::from_game(&game).unwrap();
+    let mut record = GameRecord::from_history(&history, "2026.10.18");
+    record.comment = Some(String::from("a short game"));
+    record.moves[2].comment = Some(String::from("threatens c3"));
+
+    let text = record.to_pgn();
+    assert!(text.starts_with("[PlayerOne \"alice\"]\n[PlayerTwo \"bob\"]\n"));
+    assert!(text.contains("[MoveLimit \"50\"]\n[Result \"1-0\"]\n[Reason \"three_in_a_row\"]\n"));
+    assert!(text.contains("1. b2 a2 2. a1 {threatens c3} c3"));
+    assert!(text.contains("4. b2-b1 1-0"));
+
+    let read = GameRecord::from_pgn(&text).unwrap();
+    assert_eq!(read, record);
+    let replayed = read.replay().unwrap();
+    assert_eq!(replayed.board, game.board);
+    assert_eq!(replayed.result(), game.result());
+}
+
+#[test]
+fn test_record_of_ongoing_and_resigned_games() {
+    let mut game = played(&["b2", "a1"]);
+    let record = GameRecord::from_game(&game, record::UNKNOWN_DATE);
+    assert!(record.to_pgn().contains("[Result \"*\"]"));
+    assert_eq!(GameRecord::from_pgn(&record.to_pgn()).unwrap(), record);
+
+    game.resign("bob").unwrap();
+    let record = GameRecord::from_game(&game, record::UNKNOWN_DATE);
+    let replayed = GameRecord::from_pgn(&record.to_pgn())
+        .unwrap()
+        .replay()
+        .unwrap();
+    assert_eq!(
+        replayed.result(),
+        Some(GameResult {
+            outcome: Outcome::Win {
+                player: 1,
+                reason: WinReason::Resignation
+            },
+            plies: 2
+        })
+    );
+}
+
+#[test]
+fn test_record_without_move_limit() {
+    // 60 slides after the placements, past the default limit of 50 quiet moves
+    let mut game = played(&[]);
+    game.move_limit = None;
+    while game.moves.len() < 66 {
+        let ply = game.moves.len();
+        let moves = game.legal_moves();
+        let next = (0..moves.len())
+            .map(|i| moves[(i + ply * 5) % moves.len()].clone())
+            .find(|new_move| {
+                let mut next = game.clone();
+                next.apply(new_move.clone()).unwrap();
+                next.outcome().is_none()
+            })
+            .unwrap();
+        game.apply(next).unwrap();
+    }
+
+    let record = GameRecord::from_game(&game, record::UNKNOWN_DATE);
+    let text = record.to_pgn();
+    assert!(text.contains("[MoveLimit \"none\"]\n"));
+    let read = GameRecord::from_pgn(&text).unwrap();
+    assert_eq!(read, record);
+    let replayed = read.replay().unwrap();
+    assert_eq!(replayed.board, game.board);
+    assert_eq!(replayed.move_limit, None);
+
+    // records written before the header keep the variant's limit
+    let old = text.replace("[MoveLimit \"none\"]\n", "");
+    assert!(matches!(
+        GameRecord::from_pgn(&old),
+        Err(GameError::IllegalRecordMove { ply: 57, .. })
+    ));
+}
+
+#[test]
+fn test_record_is_checked_by_the_engine() {
+    // other notations and loose layout are accepted
+    let read = GameRecord::from_pgn(
+        "[Variant \"tapatan\"]\n[Site \"club\"]\n\n1.4 0\n2. 8 {corner\nagain} 2 *\n",
+    )
+    .unwrap();
+    assert_eq!(read.moves.len(), 4);
+    assert_eq!(read.moves[2].comment.as_deref(), Some("corner again"));
+
+    assert_eq!(
+        GameRecord::from_pgn("1. b2 b2 *"),
+        Err(GameError::IllegalRecordMove {
+            ply: 2,
+            error: Box::new(GameError::PlaceOccupied { row: 1, col: 1 })
+        })
+    );
+    assert_eq!(
+        GameRecord::from_pgn("[Result \"1-0\"]\n[Reason \"three_in_a_row\"]\n\n1. b2 a1 1-0"),
+        Err(GameError::RecordResultMismatch {
+            recorded: String::from("1-0 three_in_a_row"),
+            played: String::from("*"),
+        })
+    );
+    assert!(matches!(
+        GameRecord::from_pgn("[Date \"2026.10.18\"]\n\n1. b2 a1\n2. c3 d4 *"),
+        Err(GameError::InvalidRecord { line: 4, .. })
+    ));
+    assert!(matches!(
+        GameRecord::from_pgn("1. b2 a1 3. c3 *"),
+        Err(GameError::InvalidRecord { line: 1, .. })
+    ));
+    assert!(matches!(
+        GameRecord::from_pgn("1. b2 {unclosed *"),
+        Err(GameError::InvalidRecord { line: 1, .. })
+    ));
+    assert_eq!(record::date(0), "1970.01.01");
+    assert_eq!(record::date(1_792_281_600), "2026.10.18");
+}
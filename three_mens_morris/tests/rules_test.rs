@@ -0,0 +1,81 @@
+use three_mens_morris::engine::{Outcome, WinReason};
+use three_mens_morris::error::GameError;
+use three_mens_morris::referee::Referee;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::{Move, OngoingGame};
+
+fn play(state: &mut OngoingGame, moves: &[&str]) {
+    for str_move in moves {
+        let new_move = Move::string_to_move(&str_move.to_string()).unwrap();
+        state.apply(new_move).unwrap();
+    }
+}
+
+#[test]
+fn test_three_mens_is_the_default() {
+    let state = OngoingGame::new();
+    assert_eq!(state.variant, Variant::ThreeMens);
+    assert_eq!(Variant::default(), Variant::ThreeMens);
+    assert_eq!(
+        Variant::Tapatan.rules().board.lines,
+        Variant::ThreeMens.rules().board.lines
+    );
+    assert_eq!(
+        OngoingGame::with_variant(Variant::NineMens).unwrap_err(),
+        GameError::UnsupportedVariant {
+            name: String::from("nine_mens")
+        }
+    );
+}
+
+#[test]
+fn test_nine_holes_has_no_diagonals() {
+    let mut state = OngoingGame::with_variant(Variant::NineHoles).unwrap();
+    assert_eq!(state.variant.rules().board.lines.len(), 6);
+    play(&mut state, &["a1", "b1", "b2", "a2", "c3"]);
+    // a1 b2 c3 is not a line here
+    assert_eq!(Referee::get_winner(&state), None);
+    assert_eq!(state.outcome(), None);
+    play(&mut state, &["c1"]);
+
+    // pieces cannot move through the center diagonally
+    assert_eq!(
+        state.validate(&Move::string_to_move(&String::from("b2a3")).unwrap()),
+        Err(GameError::NotAdjacent {
+            row: 1,
+            col: 1,
+            new_row: 2,
+            new_col: 0
+        })
+    );
+}
+
+#[test]
+fn test_achi_moves_after_four_pieces() {
+    let mut state = OngoingGame::with_variant(Variant::Achi).unwrap();
+    assert_eq!(state.player_one_remaining, 4);
+    assert_eq!(state.player_two_remaining, 4);
+    play(&mut state, &["a1", "b1", "c1", "b2", "a2", "c2", "b3", "a3"]);
+    assert_eq!(state.outcome(), None);
+    // only c3 is empty and b3 is player one's only piece next to it
+    assert_eq!(
+        state.legal_moves(),
+        vec![Move::string_to_move(&String::from("b3c3")).unwrap()]
+    );
+}
+
+#[test]
+fn test_achi_blocked() {
+    let mut state = OngoingGame::with_variant(Variant::Achi).unwrap();
+    state.player_one_remaining = 0;
+    state.player_two_remaining = 0;
+    state.board = [[2, 1, 1], [1, 2, 2], [1, 2, 0]];
+    assert!(state.legal_moves().is_empty());
+    assert_eq!(
+        state.outcome(),
+        Some(Outcome::Win {
+            player: 2,
+            reason: WinReason::Blocked
+        })
+    );
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use three_mens_morris::bot::Bot;
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::search::{Limits, Search, PROVEN, WIN};
+use three_mens_morris::smart_bot::SmartBot;
+use three_mens_morris::solver::{Outcome, Solution};
+use three_mens_morris::stupid_bot::StupidBot;
+use three_mens_morris::types::{Move, OngoingGame};
+
+fn best(fen: &str, depth: u32) -> (Move, i32) {
+    let game = OngoingGame::from_fen(fen).unwrap();
+    let result = Search::new().search(&game, Limits::depth(depth));
+    (result.best_move.unwrap(), result.score)
+}
+
+#[test]
+fn test_search_wins_and_blocks() {
+    // c1 finishes the first row
+    let (new_move, score) = best("oo./xx./... 1 1 1", 4);
+    assert_eq!(new_move.to_string(), "c1");
+    assert_eq!(score, WIN - 1);
+
+    // every move but c2 lets x finish the second row
+    let (new_move, _) = best("o../xx./o.. 1 1 1", 4);
+    assert_eq!(new_move.to_string(), "c2");
+
+    // the game is over, nothing to play
+    let game = OngoingGame::from_fen("ooo/xx./x.. 2 0 0").unwrap();
+    let result = Search::new().search(&game, Limits::depth(4));
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, -WIN);
+}
+
+#[test]
+fn test_search_agrees_with_solver() {
+    // short wins and losses are found at their exact distance, draws are never proven
+    let solution = Solution::solve(Variant::ThreeMens).unwrap();
+    let mut search = Search::new();
+    for (position, value) in solution.iter().step_by(7) {
+        if position.outcome().is_some() || value.distance > 5 {
+            continue;
+        }
+        let result = search.search_position(position, Limits::depth(6));
+        let distance = value.distance as i32;
+        match value.outcome {
+            Outcome::Win => assert_eq!(result.score, WIN - distance),
+            Outcome::Loss => assert_eq!(result.score, -(WIN - distance)),
+            Outcome::Draw => assert!(result.score.abs() <= PROVEN),
+        }
+        // the principal variation is a legal line
+        let mut line = *position;
+        for new_move in &result.pv {
+            let point_move = new_move.to_point_move().unwrap();
+            assert!(line.legal_moves().iter().any(|&m| m == point_move));
+            line.apply(point_move);
+        }
+    }
+}
+
+#[test]
+fn test_table_keeps_no_repetition_draws() {
+    // the first search comes back to its start on some lines and scores them as draws,
+    // two moves on that start is no longer on the line, so those draws must not be reused
+    let first = OngoingGame::from_fen("o.o/.xx/xo. 1 0 0").unwrap();
+    let later = OngoingGame::from_fen("x.o/o.x/xo. 1 0 0").unwrap();
+    let fresh = Search::new().search(&later, Limits::depth(3));
+    assert_ne!(fresh.score, 0);
+
+    let mut search = Search::new();
+    search.search(&first, Limits::depth(6));
+    assert_eq!(search.search(&later, Limits::depth(3)).score, fresh.score);
+}
+
+#[test]
+fn test_search_respects_time_limit() {
+    let game = OngoingGame::with_variant(Variant::Achi).unwrap();
+    let result = Search::new().search(&game, Limits::time(Duration::from_millis(50)));
+    assert!(result.best_move.is_some());
+    assert!(result.depth < 64);
+    assert!(game.legal_moves().contains(&result.best_move.unwrap()));
+}
+
+#[test]
+fn test_smart_bot_beats_stupid_bot() {
+    // three men's morris is won for player one, so the smart bot never lets it slip
+    let smart_bot = SmartBot::with_limits("smart-bot", Limits::depth(12));
+    let stupid_bot = StupidBot::new("stupid-bot");
+    for _ in 0..3 {
+        let mut game = OngoingGame::new();
+        game.player_one = smart_bot.id().to_string();
+        game.player_two = stupid_bot.id().to_string();
+        game.whose_turn = smart_bot.id().to_string();
+        while game.outcome().is_none() {
+            match game.turn {
+                1 => smart_bot.make_move(&mut game).unwrap(),
+                _ => stupid_bot.make_move(&mut game).unwrap(),
+            }
+        }
+        assert_eq!(Position::from_game(&game).winner(), Some(1));
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use three_mens_morris::book::Book;
+use three_mens_morris::book_bot::BookBot;
+use three_mens_morris::bot::{Difficulty, Registry};
+use three_mens_morris::mcts;
+use three_mens_morris::search::Limits;
+use three_mens_morris::selfplay::{write_csv, PlayerConfig, SelfPlay};
+use three_mens_morris::smart_bot::SmartBot;
+
+fn quick_mcts() -> PlayerConfig {
+    PlayerConfig::Mcts(mcts::Config {
+        iterations: 100,
+        temperature: 1.0,
+        ..mcts::Config::default()
+    })
+}
+
+#[test]
+fn test_selfplay_is_reproducible() {
+    let mut run = SelfPlay::new([quick_mcts(), PlayerConfig::Random], 8, 42);
+    run.threads = 1;
+    let single = run.run().unwrap();
+    run.threads = 3;
+    assert_eq!(run.run().unwrap(), single);
+    run.seed = 43;
+    assert_ne!(run.run().unwrap(), single);
+
+    // every game is there, in order, with one result for all its positions
+    let games: Vec<usize> = single.iter().map(|sample| sample.game).collect();
+    assert!(games.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(games.last(), Some(&7));
+    for sample in &single {
+        assert!((sample.policy.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!([-1, 0, 1].contains(&sample.outcome));
+        if sample.turn == 2 {
+            // random player: the policy is the move played
+            let to = sample.new_move.to_point_move().unwrap().to;
+            assert_eq!(sample.policy[to], 1.0);
+        }
+    }
+}
+
+#[test]
+fn test_selfplay_csv() {
+    let run = SelfPlay::new(
+        [PlayerConfig::Search(Limits::depth(4)), PlayerConfig::Random],
+        2,
+        7,
+    );
+    let samples = run.run().unwrap();
+    let mut out = Vec::new();
+    write_csv(&samples, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), samples.len() + 1);
+    assert!(lines[0].starts_with("game,ply,turn,one_in_hand,two_in_hand,c0,"));
+    assert!(lines.iter().all(|line| line.split(',').count() == 25));
+    assert!(lines[1].starts_with("0,0,1,3,3,0,0,0,0,0,0,0,0,0,"));
+}
+
+#[test]
+fn test_selfplay_with_registry_bot() {
+    // the simulator's book, with a shallow search out of book to keep the test quick
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../three_mens_morris_v2/simulator/weight.json");
+    let book = Arc::new(Book::load(&path).unwrap());
+    let mut registry = Registry::new();
+    registry.register("book", Difficulty::Hard, move |id| {
+        let fallback = Box::new(SmartBot::with_limits(id, Limits::depth(2)));
+        Box::new(BookBot::with_fallback(id, book.clone(), fallback))
+    });
+    assert_eq!(PlayerConfig::from_name("network", &registry), None);
+    let book = PlayerConfig::from_name("book", &registry).unwrap();
+    let random = PlayerConfig::from_name("random", &registry).unwrap();
+
+    let mut run = SelfPlay::new([book, random], 4, 5);
+    run.threads = 1;
+    let single = run.run().unwrap();
+    run.threads = 2;
+    assert_eq!(run.run().unwrap(), single);
+    for sample in single.iter().filter(|sample| sample.turn == 1) {
+        let to = sample.new_move.to_point_move().unwrap().to;
+        assert_eq!(sample.policy[to], 1.0);
+    }
+}
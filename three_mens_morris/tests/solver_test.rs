@@ -0,0 +1,76 @@
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::solver::{Outcome, Solution, Value};
+use three_mens_morris::types::OngoingGame;
+
+fn value(outcome: Outcome, distance: u8) -> Value {
+    Value { outcome, distance }
+}
+
+#[test]
+fn test_values_agree_with_their_moves() {
+    // each value is what the values one move later make of it
+    for variant in Variant::all().iter().copied().filter(|v| v.is_grid()) {
+        let solution = Solution::solve(variant).unwrap();
+        assert!(solution.len() > 1000);
+        for (position, &found) in solution.iter() {
+            if position.outcome().is_some() {
+                assert_eq!(found, value(Outcome::Loss, 0));
+                continue;
+            }
+            let children: Vec<Value> = position
+                .legal_moves()
+                .iter()
+                .map(|&new_move| {
+                    let mut child = *position;
+                    child.apply(new_move);
+                    solution.value(&child).unwrap()
+                })
+                .collect();
+            let distances = |outcome| {
+                children
+                    .iter()
+                    .filter(move |child| child.outcome == outcome)
+                    .map(|child| child.distance + 1)
+            };
+            let expected = if let Some(quickest) = distances(Outcome::Loss).min() {
+                value(Outcome::Win, quickest)
+            } else if children.iter().all(|child| child.outcome == Outcome::Win) {
+                value(Outcome::Loss, distances(Outcome::Win).max().unwrap())
+            } else {
+                value(Outcome::Draw, 0)
+            };
+            assert_eq!(found, expected, "{:?}", position.to_game().to_fen());
+        }
+    }
+}
+
+#[test]
+fn test_perfect_play() {
+    let solution = Solution::solve(Variant::ThreeMens).unwrap();
+    let mut position = Position::new(Variant::ThreeMens);
+    let start = solution.value(&position).unwrap();
+    // a forced win for the first player
+    assert_eq!(start, value(Outcome::Win, 9));
+
+    // both sides follow best_moves until the game ends, as quick as the distance says
+    for ply in 0..start.distance {
+        let moves = solution.best_moves(&position);
+        assert!(!moves.is_empty(), "no best move at ply {}", ply);
+        position.apply(moves[0]);
+    }
+    assert_eq!(position.winner(), Some(1));
+}
+
+#[test]
+fn test_probe_and_unsupported_variants() {
+    let solution = Solution::solve(Variant::ThreeMens).unwrap();
+    let mut game = OngoingGame::from_fen("oo./xx./... 1 1 1").unwrap();
+    assert_eq!(solution.probe(&game), Some(value(Outcome::Win, 1)));
+    game.apply("c1".parse().unwrap()).unwrap();
+    assert_eq!(solution.probe(&game), Some(value(Outcome::Loss, 0)));
+    game.variant = Variant::Achi;
+    assert_eq!(solution.probe(&game), None);
+
+    assert!(Solution::solve(Variant::NineMens).is_err());
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::symmetry::Transform;
+use three_mens_morris::types::Move;
+
+#[test]
+fn test_transforms_are_symmetries() {
+    let board = Variant::ThreeMens.rules();
+    for transform in Transform::all() {
+        for point in 0..9 {
+            assert_eq!(transform.inverse().point(transform.point(point)), point);
+            // neighbours stay neighbours and lines stay lines
+            for &neighbour in board.board.neighbours(point) {
+                assert!(board
+                    .board
+                    .is_adjacent(transform.point(point), transform.point(neighbour)));
+            }
+        }
+        for line in &board.board.lines {
+            let mut mapped: Vec<usize> = line.iter().map(|&p| transform.point(p)).collect();
+            mapped.sort();
+            assert!(board.board.lines.iter().any(|l| {
+                let mut l = l.clone();
+                l.sort();
+                l == mapped
+            }));
+        }
+    }
+    assert_eq!(Transform::Rotate90.point(0), 2);
+    assert_eq!(Transform::FlipDiagonal.point(1), 3);
+}
+
+#[test]
+fn test_canonical_is_shared_by_the_class() {
+    let mut position = Position::new(Variant::ThreeMens);
+    for new_move in [0, 5, 4] {
+        let moves = position.legal_moves();
+        let chosen = *moves.iter().find(|m| m.to == new_move).unwrap();
+        position.apply(chosen);
+    }
+    let (canonical, transform) = position.canonical();
+    assert_eq!(transform.position(&position), canonical);
+    for other in Transform::all() {
+        let (other_canonical, _) = other.position(&position).canonical();
+        assert_eq!(other_canonical, canonical);
+        assert_eq!(other_canonical.hash(), canonical.hash());
+    }
+
+    // a move chosen in the canonical position maps back to a legal move
+    for canonical_move in canonical.legal_moves().iter() {
+        let back = transform.inverse().point_move(canonical_move);
+        assert!(position.legal_moves().iter().any(|m| *m == back));
+    }
+}
+
+#[test]
+fn test_first_moves_collapse() {
+    // corner, edge or center
+    let start = Position::new(Variant::ThreeMens);
+    let classes: HashSet<u64> = start
+        .legal_moves()
+        .iter()
+        .map(|&new_move| {
+            let mut position = start;
+            position.apply(new_move);
+            position.canonical().0.hash()
+        })
+        .collect();
+    assert_eq!(classes.len(), 3);
+
+    let new_move = Move::string_to_move(&String::from("a1b2")).unwrap();
+    assert_eq!(
+        Transform::Rotate180.game_move(&new_move).unwrap().print(),
+        "c3-b2"
+    );
+}
@@ -0,0 +1,92 @@
+use std::env;
+use std::fs;
+
+use three_mens_morris::error::GameError;
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::solver::Solution;
+use three_mens_morris::symmetry::Transform;
+use three_mens_morris::tablebase::{self, Tablebase};
+use three_mens_morris::types::OngoingGame;
+
+fn agrees(tablebase: &Tablebase, solution: &Solution) {
+    for (position, value) in solution.iter() {
+        for transform in Transform::all() {
+            assert_eq!(
+                tablebase.probe_position(&transform.position(position)),
+                Some((value.outcome, value.distance))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_tablebase_round_trip() {
+    let solution = Solution::solve(Variant::ThreeMens).unwrap();
+    let bytes = Tablebase::from_solution(&solution).to_bytes().unwrap();
+    // 38 byte header for three_mens, then 2 bytes for each of the 2862 boards and 2 players
+    assert_eq!(tablebase::entries(), 5724);
+    assert_eq!(bytes.len(), 38 + tablebase::entries() * 2);
+    let read = Tablebase::from_bytes(&bytes).unwrap();
+    assert_eq!(read.variant, Variant::ThreeMens);
+    agrees(&read, &solution);
+
+    // games, not positions
+    let game = OngoingGame::new();
+    let start = solution.probe(&game).unwrap();
+    assert_eq!(read.probe(&game), Some((start.outcome, start.distance)));
+    let best: Vec<_> = solution
+        .best_moves(&Position::from_game(&game))
+        .iter()
+        .map(Position::to_move)
+        .collect();
+    assert_eq!(read.best_moves(&game), best);
+    assert_eq!(
+        read.probe(&OngoingGame::with_variant(Variant::Achi).unwrap()),
+        None
+    );
+}
+
+#[test]
+fn test_tablebase_file_loaded_lazily() {
+    let solution = Solution::solve(Variant::NineHoles).unwrap();
+    let path = env::temp_dir().join(format!("nine_holes_{}.tmtb", std::process::id()));
+    Tablebase::from_solution(&solution).write(&path).unwrap();
+
+    let lazy = Tablebase::open(&path).unwrap();
+    agrees(&lazy, &solution);
+    assert_eq!(lazy.verify(), Ok(()));
+    agrees(&Tablebase::load(&path).unwrap(), &solution);
+
+    // a damaged entry is caught when the file is opened, not left to probe
+    let mut bytes = fs::read(&path).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    fs::write(&path, bytes).unwrap();
+    assert!(matches!(
+        Tablebase::open(&path),
+        Err(GameError::InvalidTablebase { reason }) if reason.contains("checksum")
+    ));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_damaged_tablebase_is_refused() {
+    let solution = Solution::solve(Variant::ThreeMens).unwrap();
+    let bytes = Tablebase::from_solution(&solution).to_bytes().unwrap();
+    let reason = |bytes: &[u8]| match Tablebase::from_bytes(bytes) {
+        Err(GameError::InvalidTablebase { reason }) => reason,
+        Err(e) => panic!("{}", e),
+        Ok(_) => panic!("damaged tablebase was read"),
+    };
+
+    let mut flipped = bytes.clone();
+    *flipped.last_mut().unwrap() ^= 1;
+    assert!(reason(&flipped).contains("checksum"));
+    assert!(reason(&bytes[..bytes.len() - 1]).contains("bytes of entries"));
+    assert!(reason(&bytes[..10]).contains("cut short"));
+    assert!(reason(b"PK\x03\x04 not a tablebase").contains("not a tablebase"));
+    // pieces per player sit after the 10 byte variant name
+    let mut other_rules = bytes.clone();
+    other_rules[17] = 4;
+    assert!(reason(&other_rules).contains("other three_mens rules"));
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use three_mens_morris::position::Position;
+use three_mens_morris::rules::Variant;
+use three_mens_morris::types::{Move, OngoingGame};
+
+fn walk(position: &mut Position, depth: u32, seen: &mut HashMap<u64, ([u16; 2], [u8; 2], u8)>) {
+    // incremental hash must match a fresh one at every node, and differ between positions
+    let mut fresh = *position;
+    fresh.rehash();
+    assert_eq!(position.hash(), fresh.hash());
+    let key = (position.pieces, position.in_hand, position.turn);
+    assert_eq!(*seen.entry(position.hash()).or_insert(key), key);
+    if depth == 0 {
+        return;
+    }
+    for &new_move in position.legal_moves().iter() {
+        let before = position.hash();
+        position.apply(new_move);
+        walk(position, depth - 1, seen);
+        position.undo(new_move);
+        assert_eq!(position.hash(), before);
+    }
+}
+
+#[test]
+fn test_incremental_hash() {
+    let mut seen = HashMap::new();
+    walk(&mut Position::new(Variant::ThreeMens), 7, &mut seen);
+    assert!(seen.len() > 1000);
+}
+
+#[test]
+fn test_hash_covers_turn_hand_and_variant() {
+    let start = Position::new(Variant::ThreeMens);
+    let mut other_turn = start;
+    other_turn.turn = 2;
+    other_turn.rehash();
+    assert_ne!(start.hash(), other_turn.hash());
+
+    let mut other_hand = start;
+    other_hand.in_hand = [2, 3];
+    other_hand.rehash();
+    assert_ne!(start.hash(), other_hand.hash());
+
+    assert_ne!(start.hash(), Position::new(Variant::Tapatan).hash());
+}
+
+#[test]
+fn test_hash_is_stable() {
+    // stored keys depend on this value, it must not change between versions
+    let mut state = OngoingGame::new();
+    state
+        .apply(Move::string_to_move(&String::from("b2")).unwrap())
+        .unwrap();
+    assert_eq!(state.zobrist(), Position::from_game(&state).hash());
+    assert_eq!(state.zobrist(), 3633209059734935220);
+}
+
+#[test]
+fn test_hash_of_broken_counts() {
+    // a stored game with impossible counts hashes and is reported, instead of panicking
+    let mut state = OngoingGame::new();
+    state.player_one_remaining = 200;
+    let position = Position::from_game(&state);
+    assert_eq!(state.zobrist(), position.hash());
+    assert!(!position.illegalities().is_empty());
+}
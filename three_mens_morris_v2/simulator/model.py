@@ -1,3 +1,5 @@
+import json
+
 import numpy as np
 
 import torch
@@ -40,4 +42,10 @@ class Model(nn.Module):
         with torch.no_grad():
             pi, v = self.forward(board)
 
-        return pi.data.cpu().numpy()[0], v.data.cpu().numpy()[0]
\ No newline at end of file
+        return pi.data.cpu().numpy()[0], v.data.cpu().numpy()[0]
+
+    def export(self, path):
+        # weights as JSON lists keyed like state_dict, read by three_mens_morris/src/network.rs
+        weights = {name: tensor.tolist() for name, tensor in self.state_dict().items()}
+        with open(path, "w") as f:
+            json.dump(weights, f)